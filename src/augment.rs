@@ -0,0 +1,94 @@
+/*!
+    Graph generation by canonical vertex augmentation (McKay's method),
+    independent of the orderly algorithm in `enumerate`.
+
+    A graph on n vertices is built from each canonical graph on n - 1
+    vertices by adding a vertex with every possible neighbourhood, and kept
+    only if the new vertex is in the same automorphism orbit as a vertex
+    chosen canonically, so each isomorphism class arises from one parent.
+*/
+
+use crate::base::{BitNum,Graph,Bits};
+use crate::perm::{self,Perm};
+use crate::tools;
+use crate::enumerate;
+use rayon::prelude::*;
+use std::collections::BTreeSet;
+
+// Vertex invariant used to narrow the canonical choice of vertex
+fn invariant(gr: &Graph, v: usize) -> (usize, usize) {
+    let nbrs = (0..gr.size).filter(|&u| gr.has_edge(u, v)).map(|u| gr.degree_of(u)).sum();
+    (gr.degree_of(v), nbrs)
+}
+
+// Canonical form by perm::canonical_order, which shares nothing with
+// enumerate::to_best, and an order of the vertices giving it
+fn canonical(gr: &Graph) -> (BitNum, Vec<usize>) {
+    let degrees: Vec<usize> = (0..gr.size).map(|v| gr.degree_of(v)).collect();
+    perm::canonical_order(&degrees, |a, b| gr.has_edge(a, b) as usize,
+        |order| (gr.unrenumber(&Perm::new_unsafe(order.to_vec())).bits(), order.to_vec()))
+}
+
+fn children(size: usize, parent: BitNum) -> BTreeSet<BitNum> {
+    let last = size - 1;
+    let mut seen = BTreeSet::new();
+    for row in 0 .. 1 << last as BitNum {
+        let child = Graph::from_bits(size, parent | row << Graph::triangle(last));
+        let invs: Vec<_> = (0..size).map(|v| invariant(&child, v)).collect();
+        let top = *invs.iter().max().unwrap();
+        if invs[last] != top { continue }
+        // The canonical vertex: of those with the top invariant, the one
+        // labelled last in the canonical form.
+        let (best, order) = canonical(&child);
+        let m = order.iter().rev().cloned().find(|&v| invs[v] == top).unwrap();
+        if m != last && tools::find_automorphism(&child, &[(last, m)]).is_none() { continue }
+        seen.insert(best);
+    }
+    seen
+}
+
+/// All graphs on `size` vertices, in the canonical form of `canonical`.
+pub fn generate(size: usize) -> Vec<BitNum> {
+    if size == 0 { return Vec::new() }
+    let mut level = vec![0];
+    for n in 2..=size {
+        level = level.par_iter().flat_map_iter(|&p| children(n, p)).collect();
+    }
+    level.sort();
+    level
+}
+
+/// Graphs from the orderly enumeration and from augmentation, each sorted,
+/// the second put in the form of `enumerate::to_best` to compare them.
+pub fn cross_check(size: usize) -> (Vec<BitNum>, Vec<BitNum>) {
+    let orderly = enumerate::all_graphs(size);
+    let mut augmented: Vec<BitNum> = generate(size).into_iter()
+        .map(|bits| enumerate::to_best(&Graph::from_bits(size, bits)).bits()).collect();
+    augmented.sort();
+    (orderly, augmented)
+}
+
+/// How many of the graphs have each number of edges, as `tools::graph_counts`.
+pub fn counts_by_edges(size: usize, graphs: &[BitNum]) -> Vec<u128> {
+    let mut counts = vec![0; Graph::triangle(size) + 1];
+    for bits in graphs { counts[bits.count_ones() as usize] += 1 }
+    counts
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_generate() {
+        // https://oeis.org/A000088
+        for (size, count) in [(1, 1), (2, 2), (3, 4), (4, 11), (5, 34), (6, 156), (7, 1044)] {
+            let (orderly, augmented) = cross_check(size);
+            assert_eq!(augmented.len(), count, "size = {}", size);
+            assert_eq!(orderly, augmented, "size = {}", size);
+            let expected = tools::graph_counts(size);
+            assert_eq!(Some(counts_by_edges(size, &generate(size))), expected, "size = {}", size);
+            assert_eq!(Some(counts_by_edges(size, &orderly)), expected, "size = {}", size);
+        }
+    }
+}
@@ -1,4 +1,4 @@
-/**
+/*!
     Representation of an undirected graph.
 */
 
@@ -9,8 +9,10 @@ use std::fmt;
 type Pair = (usize, usize);
 // can be u64 if graphs max size is 11
 pub type BitNum = u128;
+// Most vertices that fit in a BitNum
+pub const MAX_SIZE: usize = 16;
 
-#[derive(Debug, PartialEq, Eq, Clone, Copy, Ord, PartialOrd)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy, Ord, PartialOrd, Default)]
 pub struct BitVec (pub BitNum);
 
 pub trait Bits {
@@ -105,7 +107,7 @@ pub fn index(a: usize, b: usize) -> usize {
 
 #[inline]
 pub fn rev_hi_index(i: usize) -> usize {
-    (((8*i + 1) as f64).sqrt() as usize + 1) / 2
+    (((8*i + 1) as f64).sqrt() as usize).div_ceil(2)
 }
 
 pub fn rev_index(i: usize) -> Pair {
@@ -145,7 +147,7 @@ impl Graph {
     pub fn has_edge_raw(&self, a: usize, b: usize) -> bool {
         self.edges[(a, b)]
     }
-    pub fn triangle(sz: usize) -> usize { sz*(sz-1)/2 }
+    pub fn triangle(sz: usize) -> usize { sz*sz.saturating_sub(1)/2 }
     pub fn from_fn(size: usize, f: impl Fn(usize, usize) -> bool) -> Self {
         let mut edges = Triangle(BitVec(0));
         for b in 1..size { for a in 0..b {
@@ -184,6 +186,80 @@ impl Graph {
     }
 }
 
+// Operations building new graphs; those that can exceed MAX_SIZE vertices
+// return None when they do.
+impl Graph {
+    pub fn checked_from_fn(size: usize, f: impl Fn(usize, usize) -> bool) -> Option<Self> {
+        if size > MAX_SIZE { None } else { Some(Graph::from_fn(size, f)) }
+    }
+    // Vertices of self first, then other
+    pub fn disjoint_union(&self, other: &Graph) -> Option<Self> {
+        let n = self.size;
+        Graph::checked_from_fn(n + other.size, |a, b|
+            if b < n { self.has_edge(a, b) } else { a >= n && other.has_edge(a - n, b - n) })
+    }
+    pub fn join(&self, other: &Graph) -> Option<Self> {
+        let n = self.size;
+        Graph::checked_from_fn(n + other.size, |a, b|
+            if b < n { self.has_edge(a, b) } else { a < n || other.has_edge(a - n, b - n) })
+    }
+    // Vertex (x, y) of a product is x * other.size + y; f gets, for each
+    // factor, whether the coordinates are equal and whether adjacent.
+    fn product(&self, other: &Graph, f: impl Fn((bool, bool), (bool, bool)) -> bool) -> Option<Self> {
+        let m = other.size;
+        Graph::checked_from_fn(self.size * m, |a, b| {
+            let (x1, y1, x2, y2) = (a / m, a % m, b / m, b % m);
+            f((x1 == x2, self.has_edge(x1, x2)), (y1 == y2, other.has_edge(y1, y2)))
+        })
+    }
+    pub fn cartesian_product(&self, other: &Graph) -> Option<Self> {
+        self.product(other, |(ex, ax), (ey, ay)| (ex && ay) || (ax && ey))
+    }
+    pub fn tensor_product(&self, other: &Graph) -> Option<Self> {
+        self.product(other, |(_, ax), (_, ay)| ax && ay)
+    }
+    pub fn strong_product(&self, other: &Graph) -> Option<Self> {
+        self.product(other, |(ex, ax), (ey, ay)| (ex || ax) && (ey || ay))
+    }
+    pub fn lexicographic_product(&self, other: &Graph) -> Option<Self> {
+        self.product(other, |(ex, ax), (_, ay)| ax || (ex && ay))
+    }
+    // Vertex i of the result is vertices[i], each of which must be a different vertex
+    pub fn induced_subgraph(&self, vertices: &[usize]) -> Self {
+        assert!(vertices.iter().enumerate().all(|(i, &v)| v < self.size && !vertices[..i].contains(&v)),
+            "Vertices {:?} not all different in {}", vertices, self);
+        Graph::from_fn(vertices.len(), |a, b| self.has_edge(vertices[a], vertices[b]))
+    }
+    // Later vertices move down by one
+    pub fn delete_vertex(&self, v: usize) -> Self {
+        let rest: Vec<usize> = (0..self.size).filter(|&x| x != v).collect();
+        self.induced_subgraph(&rest)
+    }
+    // New vertex is numbered self.size
+    pub fn add_vertex(&self, nbrs: &[usize]) -> Option<Self> {
+        let n = self.size;
+        Graph::checked_from_fn(n + 1, |a, b| if b < n { self.has_edge(a, b) } else { nbrs.contains(&a) })
+    }
+    // Merges b into a, then deletes b
+    pub fn contract_edge(&self, a: usize, b: usize) -> Self {
+        assert_ne!(a, b, "Cannot contract a loop.");
+        let mut gr = self.clone();
+        for x in 0..self.size {
+            if x != a && self.has_edge(b, x) { gr.edges.set((a, x)) }
+        }
+        gr.delete_vertex(b)
+    }
+    // Vertices are the edges in index order
+    pub fn line_graph(&self) -> Option<Self> {
+        let edges: Vec<Pair> = (0..Graph::triangle(self.size))
+            .filter(|&i| self.edges.0.get(i)).map(rev_index).collect();
+        Graph::checked_from_fn(edges.len(), |i, j| {
+            let ((a, b), (c, d)) = (edges[i], edges[j]);
+            a == c || a == d || b == c || b == d
+        })
+    }
+}
+
 impl fmt::Display for Graph {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         let v = (1 .. self.size)
@@ -251,6 +327,29 @@ mod tests {
         }
     }
     #[test]
+    fn test_operations() {
+        let k = |n| Graph::from_fn(n, |_, _| true);
+        let c = |n| Graph::from_fn(n, |a, b| b == a + 1 || (a == 0 && b == n - 1));
+        let k2 = k(2);
+        let ek = |gr: Option<Graph>| { let gr = gr.unwrap(); (gr.size, gr.edge_count()) };
+        assert_eq!(ek(c(3).disjoint_union(&c(4))), (7, 7));
+        assert_eq!(ek(k(1).join(&c(5))), (6, 10));
+        assert_eq!(ek(k2.cartesian_product(&k2)), (4, 4));
+        assert_eq!(ek(k2.tensor_product(&k2)), (4, 2));
+        assert_eq!(k2.strong_product(&k2), Some(k(4)));
+        assert_eq!(ek(k2.lexicographic_product(&Graph::from_bits(2, 0))), (4, 4));
+        assert_eq!(ek(c(4).cartesian_product(&c(4))), (16, 32));
+        assert_eq!(c(5).cartesian_product(&c(4)), None);
+        let octahedron = k(4).line_graph().unwrap();
+        assert_eq!((octahedron.size, octahedron.edge_count()), (6, 12));
+        assert_eq!(Graph::from_fn(4, |a, _| a == 0).line_graph(), Some(k(3)));
+        assert_eq!(c(5).contract_edge(0, 1).edge_count(), 4);
+        assert_eq!(c(5).delete_vertex(2).edge_count(), 3);
+        assert_eq!(c(5).delete_vertex(4).add_vertex(&[0, 3]), Some(c(5)));
+        assert_eq!(c(6).induced_subgraph(&[0, 1, 2]).edge_count(), 2);
+        assert_eq!(k(16).add_vertex(&[]), None);
+    }
+    #[test]
     fn test_show_bits() {
         let gr = Graph::from_bits(10, 0);
         assert_eq!(gr.edges.show_bits(), "0");
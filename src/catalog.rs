@@ -0,0 +1,119 @@
+/*!
+    Known dense universal ingraphs (DUIs), from the table in the README of
+    graphs mentioned in the article, with a check of the claims that are
+    feasible to recheck.
+
+    An entry for n is a graph with n vertices of room, though it may use
+    fewer: the 6 DUI only needs five.
+*/
+
+use crate::base::{BitNum,Graph,Bits};
+use crate::tools;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    // A DUI: universal, and no universal ingraph for n has more edges
+    Proven,
+    // Universal, but denser ones have not been ruled out
+    Maybe,
+    // Not known to be universal
+    Guess,
+}
+
+impl std::fmt::Display for Status {
+    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+        f.write_str(match self {
+            Status::Proven => "proven",
+            Status::Maybe => "maybe",
+            Status::Guess => "guess",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Entry {
+    pub n: usize,
+    pub bits: BitNum,
+    pub name: &'static str,
+    // None where the table makes no claim
+    pub status: Option<Status>,
+}
+
+impl Entry {
+    pub fn graph(&self) -> Graph { Graph::from_bits(self.n, self.bits) }
+    // Vertices actually used
+    pub fn vertices(&self) -> usize { tools::infer_size(self.bits) }
+}
+
+const fn entry(n: usize, bits: BitNum, name: &'static str, status: Option<Status>) -> Entry {
+    Entry { n, bits, name, status }
+}
+
+pub const CATALOG: &[Entry] = &[
+    entry(2, 1, "2 DUI", Some(Status::Proven)),
+    entry(3, 3, "3 DUI", Some(Status::Proven)),
+    entry(5, 13, "5 DUI", Some(Status::Proven)),
+    entry(6, 94, "6 DUI", Some(Status::Proven)),
+    entry(7, 1118, "7 DUI", Some(Status::Proven)),
+    entry(8, 3448, "8 DUI", Some(Status::Proven)),
+    entry(9, 101752, "9X", None),
+    entry(9, 36280, "9 DUI", Some(Status::Proven)),
+    entry(9, 36216, "9 DUI", Some(Status::Proven)),
+    entry(9, 101736, "9 DUI", Some(Status::Proven)),
+    entry(9, 101744, "9 DUI", Some(Status::Proven)),
+    entry(10, 2202040, "10 DUI", Some(Status::Proven)),
+    entry(10, 6395248, "10 DUI", Some(Status::Proven)),
+    entry(11, 6732736, "11 DUI", Some(Status::Proven)),
+    entry(12, 816167872, "12 maybe", Some(Status::Maybe)),
+    entry(13, 208052598656, "13 guess", Some(Status::Guess)),
+];
+
+// Rechecks the claims of an entry against all graphs on n vertices: that
+// it is universal, unless only a guess, and for a DUI that no graph with one
+// more edge is.  Any graph with still more edges has one of those in it.
+pub fn verify(entry: &Entry, all: &[BitNum]) -> Result<(), String> {
+    let Some(status) = entry.status else { return Ok(()) };
+    if status == Status::Guess { return Ok(()) }
+    let gr = entry.graph();
+    if let Some(ce) = tools::noncovers(all.iter().cloned(), &gr).next() {
+        return Err(format!("not universal: {}", ce))
+    }
+    if status != Status::Proven { return Ok(()) }
+    let denser = gr.edge_count() + 1;
+    let mut counterexamples = Vec::new();
+    for &ext in all.iter().filter(|bits| bits.count_ones() as usize == denser) {
+        let ext = Graph::from_bits(entry.n, ext);
+        if tools::noncovers(counterexamples.iter().cloned(), &ext).next().is_some() { continue }
+        let counter = tools::noncovers(all.iter().cloned(), &ext).next();
+        match counter {
+            Some(ce) => counterexamples.push(ce),
+            None => return Err(format!("{} with {} edges is universal", ext.bits(), denser)),
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enumerate::{self,all_graphs};
+
+    #[test]
+    fn test_catalog() {
+        for entry in CATALOG {
+            assert!(entry.vertices() <= entry.n);
+            assert!(enumerate::is_best(&entry.graph()), "{}", entry.bits);
+        }
+        for n in 2..=7 {
+            let all = all_graphs(n);
+            for entry in CATALOG.iter().filter(|e| e.n == n) {
+                assert_eq!(verify(entry, &all), Ok(()), "{}", entry.bits);
+            }
+        }
+        let all = all_graphs(6);
+        assert!(verify(&entry(6, 95, "", Some(Status::Proven)), &all).is_err());
+        // Universal, but not the densest
+        assert!(verify(&entry(6, 13, "", Some(Status::Maybe)), &all).is_ok());
+        assert!(verify(&entry(6, 13, "", Some(Status::Proven)), &all).is_err());
+    }
+}
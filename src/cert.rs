@@ -0,0 +1,239 @@
+/*!
+    Certificates for ingraph checks: for each graph H checked, where the
+    ingraph G was found, so a claim can be rechecked without a search.
+
+    The first line is `ingraph,<size>,<bits of G>`.  Each other line is
+    `<bits of H>,h,<map>` or `<bits of H>,c,<map>` when G is in H or in its
+    complement, with map giving the vertex of H for each vertex of G in
+    order, or `<bits of H>,none` when G is in neither.  For a pair, where F
+    is looked for in the complement instead, the first line ends `,<bits of F>`
+    and `c` lines map F.
+
+    Verification deliberately avoids the search in `tools::isso_inner`:
+    embeddings are checked edge by edge, and counterexamples by trying
+    every permutation.
+*/
+
+use crate::base::{BitNum,Graph,Bits};
+use crate::tools;
+use crate::enumerate;
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::{BufRead,BufReader,BufWriter,Write};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Embedding {
+    pub complement: bool,
+    pub map: Vec<usize>,
+}
+
+// Embedding of sub into sup or co into its complement, as in tools::pair_check
+pub fn find_embedding(sup: &Graph, sub_sorted: &[(usize, usize)], sub: &Graph,
+        co_sorted: &[(usize, usize)], co: &Graph) -> Option<Embedding> {
+    let found = |sub: &Graph, sub_sorted, sup: &Graph|
+        tools::isso_inner::<Option<crate::perm::Perm>>(sub, sub_sorted, sup).map(|p| p.inverse().vec);
+    found(co, co_sorted, &sup.complement()).map(|map| Embedding { complement: true, map })
+        .or_else(|| found(sub, sub_sorted, sup).map(|map| Embedding { complement: false, map }))
+}
+
+pub fn header(sub: &Graph, co: &Graph) -> String {
+    if sub == co {
+        format!("ingraph,{},{}", sub.size, sub.bits())
+    } else {
+        format!("ingraph,{},{},{}", sub.size, sub.bits(), co.bits())
+    }
+}
+
+pub fn line(sup: BitNum, emb: &Option<Embedding>) -> String {
+    match emb {
+        None => format!("{},none", sup),
+        Some(Embedding { complement, map }) => format!("{},{},{}",
+            sup,
+            if *complement { 'c' } else { 'h' },
+            map.iter().map(|x| x.to_string()).collect::<Vec<_>>().join(" ")),
+    }
+}
+
+pub fn parse_header(line: &str) -> Option<(Graph, Graph)> {
+    let mut parts = line.split(',');
+    if parts.next()? != "ingraph" { return None }
+    let size = parts.next()?.parse().ok()?;
+    let bits = parts.next()?.parse().ok()?;
+    let co = match parts.next() {
+        Some(co) => co.parse().ok()?,
+        None => bits,
+    };
+    if parts.next().is_some() { return None }
+    Some((Graph::from_bits(size, bits), Graph::from_bits(size, co)))
+}
+
+pub fn parse_line(line: &str) -> Option<(BitNum, Option<Embedding>)> {
+    let mut parts = line.split(',');
+    let sup = parts.next()?.parse().ok()?;
+    let complement = match parts.next()? {
+        "none" => return if parts.next().is_none() { Some((sup, None)) } else { None },
+        "c" => true,
+        "h" => false,
+        _ => return None,
+    };
+    let map = parts.next()?.split(' ').map(|x| x.parse().ok()).collect::<Option<Vec<_>>>()?;
+    if parts.next().is_some() { return None }
+    Some((sup, Some(Embedding { complement, map })))
+}
+
+// The map is one-to-one and takes every edge of sub (or co) to an edge of
+// the host.
+pub fn check_embedding(sub: &Graph, co: &Graph, sup: &Graph, emb: &Embedding) -> bool {
+    let (sub, host) = if emb.complement { (co, sup.complement()) } else { (sub, sup.clone()) };
+    let map = &emb.map;
+    if map.len() != sub.size || map.iter().any(|&v| v >= sup.size) { return false }
+    if map.iter().collect::<BTreeSet<_>>().len() != map.len() { return false }
+    (1..sub.size).all(|b| (0..b).all(|a| !sub.has_edge(a, b) || host.has_edge(map[a], map[b])))
+}
+
+pub fn check_counterexample(sub: &Graph, co: &Graph, sup: &Graph) -> bool {
+    !tools::naive_is_subgraph_of(sub, sup) && !tools::naive_is_subgraph_of(co, &sup.complement())
+}
+
+// A file of all graphs on size vertices: each is in canonical form, none is
+// repeated, and the number with each edge count is right.
+pub fn check_all_graphs(size: usize, path: &str) -> Result<BTreeSet<BitNum>, String> {
+    let mut seen = BTreeSet::new();
+    let mut counts = vec![0u128; Graph::triangle(size) + 1];
+    for bits in tools::try_read_graphs::<BitNum>(size, path)? {
+        let bits = bits?;
+        let gr = Graph::from_bits(size, bits);
+        if bits >> Graph::triangle(size) != 0 || !enumerate::is_best(&gr) {
+            return Err(format!("{} is not a canonical graph on {} vertices", bits, size))
+        }
+        if !seen.insert(bits) { return Err(format!("{} is repeated", bits)) }
+        counts[gr.edge_count()] += 1;
+    }
+    let expected = tools::graph_counts(size).ok_or("Cannot count graphs of this size")?;
+    for (edges, (&found, &want)) in counts.iter().zip(expected.iter()).enumerate() {
+        if found != want {
+            return Err(format!("{} graphs with {} edges, but there are {}", found, edges, want))
+        }
+    }
+    Ok(seen)
+}
+
+/// Checks a certificate, and if a file of all graphs is given, that it is
+/// complete and the certificate covers it.  Gives a summary of what was shown.
+pub fn verify(path: &str, all: Option<&str>) -> Result<String, String> {
+    let reader = BufReader::new(File::open(path).map_err(|e| format!("{}: {}", path, e))?);
+    let mut lines = reader.lines().map(|line| line.map_err(|e| format!("{}: {}", path, e)));
+    let first = lines.next().ok_or("Empty certificate")??;
+    let (sub, co) = parse_header(&first).ok_or_else(|| format!("Bad header: {}", first))?;
+    let name = if sub == co { sub.bits().to_string() } else { format!("({}, {})", sub.bits(), co.bits()) };
+    let mut covered = BTreeSet::new();
+    for (i, line) in lines.enumerate() {
+        let line = line?;
+        let (sup, emb) = parse_line(&line).ok_or_else(|| format!("Bad line {}: {}", i + 2, line))?;
+        if sup >> Graph::triangle(sub.size) != 0 {
+            return Err(format!("{} has more than {} vertices", sup, sub.size))
+        }
+        let sup_gr = Graph::from_bits(sub.size, sup);
+        match emb {
+            Some(emb) => if !check_embedding(&sub, &co, &sup_gr, &emb) {
+                return Err(format!("Bad embedding into {}: {}", sup, line))
+            },
+            None => return if check_counterexample(&sub, &co, &sup_gr) {
+                Ok(format!("{} is not an ingraph for {}: neither {} nor its complement contains it",
+                    name, sub.size, sup))
+            } else {
+                Err(format!("{} is not a counterexample", sup))
+            },
+        }
+        covered.insert(sup);
+    }
+    match all {
+        None => Ok(format!("{} embeddings of {} are valid; completeness not checked",
+            covered.len(), name)),
+        Some(all) => {
+            let all = check_all_graphs(sub.size, all)?;
+            if let Some(missing) = all.difference(&covered).next() {
+                return Err(format!("No embedding into {}", missing))
+            }
+            Ok(format!("{} is an ingraph for {}: embeds in all {} graphs or their complements",
+                name, sub.size, all.len()))
+        },
+    }
+}
+
+pub struct Writer { out: BufWriter<File> }
+
+impl Writer {
+    pub fn create(path: &str, sub: &Graph, co: &Graph) -> Self {
+        let mut out = BufWriter::new(File::create(path).unwrap());
+        writeln!(out, "{}", header(sub, co)).unwrap();
+        Writer { out }
+    }
+    pub fn add(&mut self, sup: BitNum, emb: &Option<Embedding>) {
+        writeln!(self.out, "{}", line(sup, emb)).unwrap();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use crate::base::random_graph;
+
+    #[test]
+    fn test_lines() {
+        let emb = Some(Embedding { complement: true, map: vec![2, 0, 1] });
+        assert_eq!(parse_line(&line(5, &emb)), Some((5, emb)));
+        assert_eq!(parse_line(&line(7, &None)), Some((7, None)));
+        let (sub, co) = (Graph::from_bits(6, 94), Graph::from_bits(6, 7));
+        assert_eq!(parse_header(&header(&sub, &sub)), Some((sub.clone(), sub.clone())));
+        assert_eq!(parse_header(&header(&sub, &co)), Some((sub, co)));
+        for bad in ["5", "5,x,0", "5,h,0 a", "5,none,1", "x,h,0"] {
+            assert_eq!(parse_line(bad), None);
+        }
+        let sub = Graph::from_bits(3, 0b011);
+        let sup = Graph::from_bits(3, 0b101);
+        assert!(check_embedding(&sub, &sub, &sup, &Embedding { complement: false, map: vec![1, 0, 2] }));
+        assert!(!check_embedding(&sub, &sub, &sup, &Embedding { complement: false, map: vec![0, 1, 2] }));
+        assert!(!check_embedding(&sub, &sub, &sup, &Embedding { complement: false, map: vec![0, 0, 1] }));
+    }
+
+    #[test]
+    fn test_embedding() {
+        let rng = &mut rand::thread_rng();
+        for _ in 0 .. 500 {
+            let size = rng.gen_range(2..=8);
+            let sub = random_graph(rng, size);
+            let sup = random_graph(rng, size);
+            let sorted = tools::build_sorted_row(&sub);
+            let emb = find_embedding(&sup, &sorted, &sub, &sorted, &sub);
+            assert_eq!(emb.is_some(), tools::ingraph_check(&sup, &sorted, &sub));
+            if let Some(Embedding { complement, map }) = emb {
+                let host = if complement { sup.complement() } else { sup.clone() };
+                for b in 1..size { for a in 0..b {
+                    assert!(!sub.has_edge(a, b) || host.has_edge(map[a], map[b]));
+                }}
+            }
+        }
+    }
+
+    #[test]
+    fn test_verify_errors() {
+        let dir = std::env::temp_dir().join(format!("cert-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let file = |name: &str, text: &[u8]| {
+            let path = dir.join(name);
+            std::fs::write(&path, text).unwrap();
+            path.to_str().unwrap().to_string()
+        };
+        let missing = dir.join("missing").to_str().unwrap().to_string();
+        assert!(verify(&missing, None).is_err());
+        let sub = Graph::from_bits(3, 1);
+        let cert = file("cert", format!("{}\n", header(&sub, &sub)).as_bytes());
+        assert!(verify(&cert, None).is_ok());
+        assert!(verify(&file("binary", b"\xff\xfe\n"), None).is_err());
+        assert!(verify(&cert, Some(&missing)).is_err());
+        assert!(verify(&cert, Some(&file("all", b"0\nx\n"))).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
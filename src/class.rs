@@ -0,0 +1,336 @@
+/*!
+    Classes of graphs, to ask whether a graph is universal only over the
+    graphs H in a class.  Written on the command line as `triangle-free`,
+    `bipartite`, `planar`, `regular`, `self-complementary` or
+    `degrees(3,3,2,2,2)` for a degree sequence.
+*/
+
+use crate::base::{BitNum,Graph,Bits};
+use crate::tools;
+use crate::enumerate;
+use crate::selfcomp;
+use std::collections::VecDeque;
+use std::sync::Mutex;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Class {
+    TriangleFree,
+    Bipartite,
+    Planar,
+    Regular,
+    SelfComplementary,
+    // Degrees in decreasing order
+    Degrees(Vec<usize>),
+}
+
+impl std::str::FromStr for Class {
+    type Err = String;
+    fn from_str(s: &str) -> Result<Self, String> {
+        match s.trim() {
+            "triangle-free" => Ok(Class::TriangleFree),
+            "bipartite" => Ok(Class::Bipartite),
+            "planar" => Ok(Class::Planar),
+            "regular" => Ok(Class::Regular),
+            "self-complementary" => Ok(Class::SelfComplementary),
+            s => {
+                let args = s.strip_prefix("degrees(").and_then(|s| s.strip_suffix(')'))
+                    .ok_or_else(|| format!("Unknown class: {}", s))?;
+                let mut degs = args.split(',').map(|d| d.trim().parse::<usize>())
+                    .collect::<Result<Vec<_>, _>>().map_err(|e| format!("{}: {}", s, e))?;
+                degs.sort_by(|a, b| b.cmp(a));
+                Ok(Class::Degrees(degs))
+            },
+        }
+    }
+}
+
+impl Class {
+    pub fn contains(&self, gr: &Graph) -> bool {
+        match self {
+            Class::TriangleFree => !tools::is_subgraph_of(&Graph::from_bits(gr.size, 7), gr),
+            Class::Bipartite => two_colour(gr).is_some(),
+            Class::Planar => is_planar(gr),
+            Class::Regular => (0..gr.size).all(|v| gr.degree_of(v) == gr.degree_of(0)),
+            Class::SelfComplementary => selfcomp::is_self_complementary(gr).is_some(),
+            Class::Degrees(degs) => {
+                let mut row = tools::degree_row(gr);
+                row.sort_by(|a, b| b.cmp(a));
+                row == *degs
+            },
+        }
+    }
+
+    // Whether complements of graphs in the class are in it, which lets a
+    // scan check only one of each graph and its complement.
+    pub fn is_complement_closed(&self, size: usize) -> bool {
+        match self {
+            Class::Regular | Class::SelfComplementary => true,
+            Class::Degrees(degs) => {
+                let mut co: Vec<usize> = degs.iter().map(|&d| (size - 1).saturating_sub(d)).collect();
+                co.sort_by(|a, b| b.cmp(a));
+                co == *degs
+            },
+            _ => false,
+        }
+    }
+
+    // Whether the class is closed under removing edges, so a search that
+    // only adds edges can give up as soon as it leaves.
+    pub fn is_monotone(&self) -> bool {
+        matches!(self, Class::TriangleFree | Class::Bipartite | Class::Planar)
+    }
+}
+
+/// Graphs among `sups` in the class that the target does not cover.
+pub fn noncovers<B: Bits + Copy, V: Iterator<Item=B>>(sups: V, target: tools::Target, class: &Class)
+        -> impl Iterator<Item=B> + use<B, V> {
+    let closed = class.is_complement_closed(target.size);
+    let class = class.clone();
+    tools::noncovers_within(sups, target, move |gr| class.contains(gr), closed)
+}
+
+/// All graphs on `size` vertices in the class, in canonical form.
+pub fn graphs(size: usize, class: &Class) -> Vec<BitNum> {
+    if *class == Class::SelfComplementary {
+        return selfcomp::self_complementary(size).into_iter().collect()
+    }
+    let range = match class {
+        Class::Degrees(degs) => Some((degs.iter().sum::<usize>() / 2, degs.iter().sum::<usize>() / 2)),
+        _ => None,
+    };
+    let found = Mutex::new(Vec::new());
+    enumerate::enumerate_graphs(size, range, |bits| {
+        if class.contains(&Graph::from_bits(size, bits)) { found.lock().unwrap().push(bits) }
+    });
+    let mut found = found.into_inner().unwrap();
+    found.sort();
+    found
+}
+
+// Colour of each vertex, if there are no odd cycles
+pub fn two_colour(gr: &Graph) -> Option<Vec<bool>> {
+    let mut colour: Vec<Option<bool>> = vec![None; gr.size];
+    for start in 0..gr.size {
+        if colour[start].is_some() { continue }
+        colour[start] = Some(false);
+        let mut queue = VecDeque::from([start]);
+        while let Some(v) = queue.pop_front() {
+            let c = colour[v].unwrap();
+            for u in (0..gr.size).filter(|&u| u != v && gr.has_edge(u, v)) {
+                match colour[u] {
+                    None => { colour[u] = Some(!c); queue.push_back(u) },
+                    Some(d) if d == c => return None,
+                    _ => (),
+                }
+            }
+        }
+    }
+    Some(colour.into_iter().map(|c| c.unwrap()).collect())
+}
+
+// Vertex sets of the biconnected components with at least one edge
+fn blocks(gr: &Graph) -> Vec<Vec<usize>> {
+    struct State { disc: Vec<usize>, low: Vec<usize>, time: usize, stack: Vec<(usize, usize)>, blocks: Vec<Vec<usize>> }
+    fn visit(gr: &Graph, st: &mut State, v: usize, parent: usize) {
+        st.time += 1;
+        st.disc[v] = st.time;
+        st.low[v] = st.time;
+        for u in (0..gr.size).filter(|&u| u != v && gr.has_edge(u, v)) {
+            if st.disc[u] == 0 {
+                st.stack.push((v, u));
+                visit(gr, st, u, v);
+                st.low[v] = st.low[v].min(st.low[u]);
+                if st.low[u] >= st.disc[v] {
+                    let mut block = Vec::new();
+                    while let Some((a, b)) = st.stack.pop() {
+                        block.push(a);
+                        block.push(b);
+                        if (a, b) == (v, u) { break }
+                    }
+                    block.sort();
+                    block.dedup();
+                    st.blocks.push(block);
+                }
+            } else if u != parent && st.disc[u] < st.disc[v] {
+                st.stack.push((v, u));
+                st.low[v] = st.low[v].min(st.disc[u]);
+            }
+        }
+    }
+    let mut st = State { disc: vec![0; gr.size], low: vec![0; gr.size], time: 0, stack: Vec::new(), blocks: Vec::new() };
+    for v in 0..gr.size {
+        if st.disc[v] == 0 { visit(gr, &mut st, v, usize::MAX) }
+    }
+    st.blocks
+}
+
+/// Planarity, by testing each block with the algorithm of Demoucron,
+/// Malgrange and Pertuiset.
+pub fn is_planar(gr: &Graph) -> bool {
+    blocks(gr).iter().all(|vs| {
+        let block = gr.induced_subgraph(vs);
+        vs.len() <= 4 || (block.edge_count() <= 3 * vs.len() - 6 && dmp(&block))
+    })
+}
+
+// Path from one vertex of the embedded part to another through a fragment:
+// a chord, or a component of the rest with the edges attaching it.
+fn fragment_path(gr: &Graph, in_h: &[bool], comp: &[usize], contacts: &[usize]) -> Vec<usize> {
+    let u = contacts[0];
+    let start = *comp.iter().find(|&&x| gr.has_edge(u, x)).unwrap();
+    let mut prev = vec![usize::MAX; gr.size];
+    prev[start] = start;
+    let mut queue = VecDeque::from([start]);
+    while let Some(x) = queue.pop_front() {
+        if let Some(&v) = contacts.iter().find(|&&v| v != u && gr.has_edge(x, v)) {
+            let mut path = vec![v, x];
+            let mut y = x;
+            while prev[y] != y { y = prev[y]; path.push(y) }
+            path.push(u);
+            path.reverse();
+            return path;
+        }
+        for &y in comp {
+            if !in_h[y] && prev[y] == usize::MAX && gr.has_edge(x, y) {
+                prev[y] = x;
+                queue.push_back(y);
+            }
+        }
+    }
+    unreachable!("fragment of a biconnected graph with one contact")
+}
+
+#[allow(clippy::needless_range_loop)]
+fn dmp(gr: &Graph) -> bool {
+    let n = gr.size;
+    let adj = |a: usize, b: usize| a != b && gr.has_edge(a, b);
+    // A first cycle through the edge from 0 to its first neighbour
+    let w = (1..n).find(|&w| adj(0, w)).unwrap();
+    let mut prev = vec![usize::MAX; n];
+    prev[w] = w;
+    let mut queue = VecDeque::from([w]);
+    while let Some(x) = queue.pop_front() {
+        for y in 0..n {
+            if adj(x, y) && prev[y] == usize::MAX && (x, y) != (w, 0) {
+                prev[y] = x;
+                queue.push_back(y);
+            }
+        }
+    }
+    let mut cycle = vec![0];
+    while *cycle.last().unwrap() != w { cycle.push(prev[*cycle.last().unwrap()]) }
+    let mut in_h = vec![false; n];
+    let mut h = Graph::from_bits(n, 0);
+    for (i, &v) in cycle.iter().enumerate() {
+        in_h[v] = true;
+        h.edges.set((v, cycle[(i + 1) % cycle.len()]));
+    }
+    let mut faces = vec![cycle.clone(), cycle];
+    while h.edge_count() < gr.edge_count() {
+        // Fragments as (vertices outside h, contacts, path to embed)
+        let mut fragments: Vec<(Vec<usize>, Vec<usize>)> = Vec::new();
+        for b in 1..n { for a in 0..b {
+            if in_h[a] && in_h[b] && adj(a, b) && !h.has_edge(a, b) { fragments.push((vec![], vec![a, b])) }
+        }}
+        let mut seen = in_h.clone();
+        for s in 0..n {
+            if seen[s] { continue }
+            seen[s] = true;
+            let mut comp = vec![s];
+            let mut i = 0;
+            while i < comp.len() {
+                let x = comp[i];
+                for y in 0..n {
+                    if !seen[y] && adj(x, y) { seen[y] = true; comp.push(y) }
+                }
+                i += 1;
+            }
+            let contacts = (0..n).filter(|&v| in_h[v] && comp.iter().any(|&x| adj(x, v))).collect();
+            fragments.push((comp, contacts));
+        }
+        let admissible = |contacts: &[usize]| -> Vec<usize> {
+            (0..faces.len()).filter(|&f| contacts.iter().all(|v| faces[f].contains(v))).collect()
+        };
+        let mut choice = None;
+        for (comp, contacts) in &fragments {
+            let fs = admissible(contacts);
+            if fs.is_empty() { return false }
+            if fs.len() == 1 || choice.is_none() { choice = Some((comp, contacts, fs[0])) }
+            if fs.len() == 1 { break }
+        }
+        let (comp, contacts, f) = choice.unwrap();
+        let path = if comp.is_empty() { contacts.clone() } else { fragment_path(gr, &in_h, comp, contacts) };
+        for i in 0..path.len() - 1 {
+            in_h[path[i]] = true;
+            h.edges.set((path[i], path[i + 1]));
+        }
+        // Split the face along the path from u to v
+        let face = &faces[f];
+        let (u, v) = (path[0], *path.last().unwrap());
+        let iu = face.iter().position(|&x| x == u).unwrap();
+        let face: Vec<usize> = face[iu..].iter().chain(&face[..iu]).cloned().collect();
+        let iv = face.iter().position(|&x| x == v).unwrap();
+        let inner = &path[1..path.len() - 1];
+        let one: Vec<usize> = face[..=iv].iter().chain(inner.iter().rev()).cloned().collect();
+        let two: Vec<usize> = face[iv..].iter().chain(&face[..1]).chain(inner).cloned().collect();
+        faces[f] = one;
+        faces.push(two);
+    }
+    true
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::families;
+
+    #[test]
+    fn test_planar() {
+        for gr in [families::complete(5), families::complete_bipartite(3, 3), families::petersen()] {
+            assert!(!is_planar(&gr), "{}", gr);
+        }
+        for gr in [families::complete(4), families::wheel(9), families::complete_bipartite(2, 7),
+                families::cycle(12), families::friendship(4)] {
+            assert!(is_planar(&gr), "{}", gr);
+        }
+        // https://oeis.org/A005470
+        for (size, count) in [(4, 11), (5, 33), (6, 142), (7, 822)] {
+            assert_eq!(graphs(size, &Class::Planar).len(), count, "size = {}", size);
+        }
+    }
+
+    #[test]
+    fn test_restricted() {
+        // K3 is in no bipartite graph, and in the complement of each one
+        // with a part of three: on four vertices C4, P4 and 2K2 lack it
+        for (size, lacking) in [(4, 3), (5, 0)] {
+            let target = tools::Target::family(&[Graph::from_bits(size, 7)]);
+            let all = crate::enumerate::all_graphs(size);
+            let found: Vec<_> = noncovers(all.iter().cloned(), target.clone(), &Class::Bipartite).collect();
+            let naive: Vec<_> = all.iter().cloned().filter(|&bits| {
+                let gr = Graph::from_bits(size, bits);
+                Class::Bipartite.contains(&gr) && !target.covers(&gr)
+            }).collect();
+            assert_eq!(found.len(), lacking);
+            assert_eq!(found, naive);
+            let (ce, _) = crate::seek::seek_target(&target, usize::MAX, Some(&|gr: &Graph| Class::Bipartite.contains(gr)));
+            assert_eq!(ce.is_some(), lacking > 0);
+            if let Some(ce) = ce { assert!(Class::Bipartite.contains(&ce) && !target.covers(&ce)) }
+        }
+    }
+
+    #[test]
+    fn test_classes() {
+        let count = |size, class: &str| graphs(size, &class.parse().unwrap()).len();
+        // https://oeis.org/A006785, A033995, A005176
+        assert_eq!(count(7, "triangle-free"), 107);
+        assert_eq!(count(7, "bipartite"), 88);
+        assert_eq!(count(8, "regular"), 22);
+        assert_eq!(count(8, "self-complementary"), 10);
+        assert_eq!(count(5, "degrees(2,2,2,2,2)"), 1);
+        assert_eq!(count(6, "degrees(1, 1, 1, 1, 1, 1)"), 1);
+        assert!("degrees(1,x)".parse::<Class>().is_err());
+        assert!(Class::Degrees(vec![2, 2, 2, 2, 2]).is_complement_closed(5));
+        assert!(!Class::Degrees(vec![1, 1, 0]).is_complement_closed(3));
+    }
+}
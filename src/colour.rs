@@ -0,0 +1,190 @@
+/*!
+    Edge colourings of K_n with r colours, for the r-colour version of the
+    problem: G is r-universal for n if every r-colouring of K_n has G in one
+    colour.  With r = 2 this is the ordinary problem, a graph and its
+    complement being the two colours.
+*/
+
+use crate::base::{BitNum,Graph,Bits,raw_index};
+use crate::perm::{self,Perm};
+use crate::tools;
+use itertools::Itertools;
+use std::collections::BTreeSet;
+use std::fmt;
+
+/// A colouring as the graph of each colour, by bits; the colours partition
+/// the edges.
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Colouring {
+    pub size: usize,
+    pub classes: Vec<BitNum>,
+}
+
+impl Colouring {
+    pub fn from_fn(size: usize, colours: usize, f: impl Fn(usize, usize) -> usize) -> Self {
+        let mut classes = vec![0; colours];
+        for b in 1..size { for a in 0..b {
+            classes[f(a, b)] |= 1 << raw_index(a, b);
+        }}
+        Colouring { size, classes }
+    }
+    pub fn colours(&self) -> usize { self.classes.len() }
+    pub fn graph(&self, c: usize) -> Graph { Graph::from_bits(self.size, self.classes[c]) }
+    // Vertex i becomes p(i), as for Graph::unrenumber
+    pub fn unrenumber(&self, p: &Perm) -> Self {
+        let classes = (0..self.colours()).map(|c| self.graph(c).unrenumber(p).bits()).collect();
+        Colouring { size: self.size, classes }
+    }
+    // Colour c becomes sigma[c]
+    pub fn recolour(&self, sigma: &[usize]) -> Self {
+        let mut classes = vec![0; self.colours()];
+        for (c, &bits) in self.classes.iter().enumerate() { classes[sigma[c]] = bits }
+        Colouring { size: self.size, classes }
+    }
+    // Colour with a copy of sub
+    pub fn find(&self, sub_sorted: &[(usize, usize)], sub: &Graph) -> Option<usize> {
+        (0..self.colours()).find(|&c| tools::isso_inner(sub, sub_sorted, &self.graph(c)))
+    }
+}
+
+impl fmt::Display for Colouring {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.classes.iter().map(|c| c.to_string()).collect::<Vec<_>>().join(" "))
+    }
+}
+
+// Least relabelling over vertex permutations and the given colour permutations
+fn canonical_with(col: &Colouring, sigmas: impl Iterator<Item=Vec<usize>>) -> Colouring {
+    let n = col.size;
+    let mut best: Option<Colouring> = None;
+    for sigma in sigmas {
+        let c = col.recolour(&sigma);
+        let degs: Vec<Vec<usize>> = (0..n)
+            .map(|v| (0..c.colours()).map(|k| c.graph(k).degree_of(v)).collect()).collect();
+        let colour = |u, v| (0..c.colours()).find(|&k| c.graph(k).has_edge(u, v)).unwrap();
+        let cand = perm::canonical_order(&degs, colour, |order| c.unrenumber(&Perm::new_unsafe(order.to_vec())));
+        if best.as_ref().is_none_or(|b| cand < *b) { best = Some(cand) }
+    }
+    best.unwrap()
+}
+
+/// Canonical form up to permuting vertices and colours.
+pub fn canonical(col: &Colouring) -> Colouring {
+    canonical_with(col, (0..col.colours()).permutations(col.colours()))
+}
+
+/// All colourings of K_size with the given number of colours, up to
+/// permuting vertices and colours, built up a vertex at a time.
+pub fn all_colourings(size: usize, colours: usize) -> BTreeSet<Colouring> {
+    let mut level: BTreeSet<Colouring> = BTreeSet::new();
+    if size == 0 { return level }
+    level.insert(Colouring { size: 1, classes: vec![0; colours] });
+    for n in 2..=size {
+        let mut next = BTreeSet::new();
+        for parent in &level {
+            for row in (0..n - 1).map(|_| 0..colours).multi_cartesian_product() {
+                let mut classes = parent.classes.clone();
+                for (a, &c) in row.iter().enumerate() { classes[c] |= 1 << raw_index(a, n - 1) }
+                next.insert(canonical(&Colouring { size: n, classes }));
+            }
+        }
+        level = next;
+    }
+    level
+}
+
+/// Colourings with no colour containing sub.
+pub fn noncovers<'a>(cols: impl Iterator<Item=&'a Colouring>, sub: &Graph) -> impl Iterator<Item=&'a Colouring> {
+    let sub_sorted = tools::build_sorted_row(sub);
+    let sub = sub.clone();
+    cols.filter(move |col| col.find(&sub_sorted, &sub).is_none())
+}
+
+/// Looks for a colouring of K_{sub.size} with no colour containing sub, as
+/// in `seek::seek`: the last colour starts with every edge, and edges of a
+/// copy of sub in it are moved to other colours that stay without sub.
+pub fn seek(sub: &Graph, colours: usize, bailout: usize) -> (Option<Colouring>, usize) {
+    if colours < 2 { return (None, 0) }
+    let n = sub.size;
+    let sub_sorted = tools::build_sorted_row(sub);
+    let last = colours - 1;
+    let mut seen = BTreeSet::new();
+    // Up to permutations fixing the last colour
+    let key = |col: &Colouring| canonical_with(col,
+        (0..last).permutations(last).map(|mut sigma| { sigma.push(last); sigma }));
+    fn recurse(col: Colouring, sub: &Graph, sub_sorted: &[(usize, usize)], bailout: usize,
+            seen: &mut BTreeSet<Colouring>, key: &dyn Fn(&Colouring) -> Colouring) -> Option<Colouring> {
+        let last = col.colours() - 1;
+        let Some(copy) = tools::find_subgraph_ss(sub, sub_sorted, &col.graph(last)) else { return Some(col) };
+        for ix in (0..Graph::triangle(copy.size)).filter(|&ix| copy.bits() & 1 << ix != 0) {
+            for c in 0..last {
+                let mut next = col.clone();
+                next.classes[last] &= !(1 << ix);
+                next.classes[c] |= 1 << ix;
+                if tools::isso_inner(sub, sub_sorted, &next.graph(c)) { continue }
+                if seen.len() >= bailout { return None }
+                if !seen.insert(key(&next)) { continue }
+                if let Some(found) = recurse(next, sub, sub_sorted, bailout, seen, key) { return Some(found) }
+            }
+        }
+        None
+    }
+    let start = Colouring::from_fn(n, colours, |_, _| last);
+    let found = recurse(start, sub, &sub_sorted, bailout, &mut seen, &key);
+    (found, seen.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perm::all_perms;
+    use crate::families;
+
+    // Colourings up to vertex and colour permutations by Burnside's lemma
+    fn burnside(size: usize, colours: usize) -> u128 {
+        let mut total = 0;
+        let mut group = 0;
+        for p in all_perms(size) {
+            let orbits = tools::pair_orbits(&p);
+            for sigma in all_perms(colours) {
+                group += 1;
+                // Colours c with sigma^len(c) = c can colour an orbit of length len
+                total += orbits.iter().map(|orbit| (0..colours).filter(|&c| {
+                    (0..orbit.len()).fold(c, |x, _| sigma.apply(x)) == c
+                }).count() as u128).product::<u128>();
+            }
+        }
+        total / group
+    }
+
+    #[test]
+    fn test_canonical() {
+        for (size, colours) in [(3, 3), (4, 3), (5, 3), (5, 2), (4, 4)] {
+            let all = all_colourings(size, colours);
+            assert_eq!(all.len() as u128, burnside(size, colours), "{} {}", size, colours);
+        }
+        let col = Colouring::from_fn(5, 3, |a, b| (a + 2 * b) % 3);
+        let p = Perm::new_unsafe(vec![3, 0, 4, 1, 2]);
+        assert_eq!(canonical(&col), canonical(&col.unrenumber(&p).recolour(&[2, 0, 1])));
+        // One colour on many vertices, as seek starts from
+        let one = Colouring::from_fn(12, 3, |_, _| 2);
+        assert_eq!(canonical(&one).classes[..2], [0, 0]);
+    }
+
+    #[test]
+    fn test_universal() {
+        // K4 splits into three perfect matchings, but K5 cannot be 3-edge-coloured
+        let p3 = families::path(3);
+        let pad = |n| Graph::from_bits(n, p3.bits());
+        assert_eq!(noncovers(all_colourings(4, 3).iter(), &pad(4)).count(), 1);
+        assert_eq!(noncovers(all_colourings(5, 3).iter(), &pad(5)).count(), 0);
+        let (found, _) = seek(&pad(4), 3, usize::MAX);
+        let found = found.unwrap();
+        assert!(found.find(&tools::build_sorted_row(&pad(4)), &pad(4)).is_none());
+        assert_eq!(seek(&pad(5), 3, usize::MAX).0, None);
+        // R(3, 3) = 6 with two colours
+        let k3 = |n| Graph::from_bits(n, 7);
+        assert!(seek(&k3(5), 2, usize::MAX).0.is_some());
+        assert_eq!(noncovers(all_colourings(6, 2).iter(), &k3(6)).count(), 0);
+    }
+}
@@ -0,0 +1,228 @@
+/*!
+    Directed graphs, for the oriented version of the problem: D is a
+    universal ingraph for n if every tournament on n vertices contains it.
+
+    Each pair a < b has two bits, 2 * raw_index(a, b) for the arc a→b and
+    the one after it for b→a, so n vertices take n × (n - 1) bits and, as for
+    graphs, the bits do not depend on the size.
+*/
+
+use crate::base::{BitNum,Bits,raw_index};
+use crate::perm::{self,Perm};
+use std::collections::BTreeSet;
+use std::fmt;
+
+// Most vertices that fit in a BitNum
+pub const MAX_SIZE: usize = 11;
+
+#[derive(Debug, PartialEq, Eq, Clone, PartialOrd, Ord, Hash)]
+pub struct Digraph { pub size: usize, pub arcs: BitNum }
+
+#[inline]
+pub fn arc_index(a: usize, b: usize) -> usize {
+    if a < b { 2 * raw_index(a, b) } else { 2 * raw_index(b, a) + 1 }
+}
+
+impl Digraph {
+    pub fn from_fn(size: usize, f: impl Fn(usize, usize) -> bool) -> Self {
+        let mut arcs = 0;
+        for a in 0..size { for b in 0..size {
+            if a != b && f(a, b) { arcs |= 1 << arc_index(a, b) }
+        }}
+        Digraph { size, arcs }
+    }
+    pub fn has_arc(&self, a: usize, b: usize) -> bool {
+        a != b && self.arcs & 1 << arc_index(a, b) != 0
+    }
+    pub fn out_degree(&self, v: usize) -> usize { (0..self.size).filter(|&u| self.has_arc(v, u)).count() }
+    pub fn in_degree(&self, v: usize) -> usize { (0..self.size).filter(|&u| self.has_arc(u, v)).count() }
+    pub fn arc_count(&self) -> usize { self.arcs.count_ones() as usize }
+    // Vertex a becomes p(a), as for Graph::unrenumber
+    pub fn unrenumber(&self, p: &Perm) -> Self {
+        Digraph::from_fn(self.size, |a, b| self.has_arc(p.apply(a), p.apply(b)))
+    }
+    pub fn reverse(&self) -> Self { Digraph::from_fn(self.size, |a, b| self.has_arc(b, a)) }
+    // Exactly one arc between each pair
+    pub fn is_tournament(&self) -> bool {
+        (1..self.size).all(|b| (0..b).all(|a| self.has_arc(a, b) != self.has_arc(b, a)))
+    }
+    // Arc a→b exactly when a < b
+    pub fn transitive(size: usize) -> Self { Digraph::from_fn(size, |a, b| a < b) }
+    pub fn path(size: usize) -> Self { Digraph::from_fn(size, |a, b| b == a + 1) }
+    pub fn cycle(size: usize) -> Self { Digraph::from_fn(size, |a, b| b == (a + 1) % size) }
+    // Arc a→b when b - a is a nonzero square mod p, for a prime p = 3 mod 4
+    pub fn paley(p: usize) -> Self {
+        let squares: BTreeSet<usize> = (1..p).map(|x| x * x % p).collect();
+        Digraph::from_fn(p, |a, b| squares.contains(&((b + p - a) % p)))
+    }
+}
+
+impl fmt::Display for Digraph {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let v = (1..self.size)
+            .flat_map(|b| (0..b).flat_map(move |a| [(a, b), (b, a)]))
+            .filter(|&(a, b)| self.has_arc(a, b))
+            .map(|(a, b)| format!("{}→{}", a, b))
+            .collect::<Vec<_>>();
+        write!(f, "[{}]", v.join(" "))
+    }
+}
+
+impl Bits for Digraph {
+    fn bits(&self) -> BitNum { self.arcs }
+    fn from_bits(size: usize, bits: BitNum) -> Self { Digraph { size, arcs: bits } }
+}
+
+/// Canonical form: the least bits over relabellings by out- and in-degree,
+/// ties broken as in `perm::canonical_order`.
+pub fn to_best(dg: &Digraph) -> Digraph {
+    let degrees: Vec<_> = (0..dg.size).map(|v| (dg.out_degree(v), dg.in_degree(v))).collect();
+    perm::canonical_order(&degrees, |a, b| dg.has_arc(a, b) as usize,
+        |order| dg.unrenumber(&Perm::new_unsafe(order.to_vec())))
+}
+
+// Out- and in-degree of each vertex
+type Degrees = [(usize, usize)];
+
+/// Where each vertex of sub goes in sup, if sub is a subdigraph of it;
+/// sub can have fewer vertices.
+pub fn find_embedding(sub: &Digraph, sup: &Digraph) -> Option<Vec<usize>> {
+    if sub.size > sup.size { return None }
+    // Most constrained vertices first
+    let mut order: Vec<usize> = (0..sub.size).collect();
+    order.sort_by_key(|&v| std::cmp::Reverse(sub.out_degree(v) + sub.in_degree(v)));
+    let degs = |dg: &Digraph| (0..dg.size).map(|v| (dg.out_degree(v), dg.in_degree(v))).collect::<Vec<_>>();
+    let (sub_degs, sup_degs) = (degs(sub), degs(sup));
+    let mut emb = vec![usize::MAX; sub.size];
+    let mut used = vec![false; sup.size];
+    fn go(i: usize, order: &[usize], sub: &Digraph, sup: &Digraph, degs: (&Degrees, &Degrees),
+            emb: &mut [usize], used: &mut [bool]) -> bool {
+        let Some(&v) = order.get(i) else { return true };
+        for w in 0..sup.size {
+            if used[w] || degs.0[v].0 > degs.1[w].0 || degs.0[v].1 > degs.1[w].1 { continue }
+            let fits = order[..i].iter().all(|&u| {
+                (!sub.has_arc(u, v) || sup.has_arc(emb[u], w)) && (!sub.has_arc(v, u) || sup.has_arc(w, emb[u]))
+            });
+            if !fits { continue }
+            emb[v] = w;
+            used[w] = true;
+            if go(i + 1, order, sub, sup, degs, emb, used) { return true }
+            used[w] = false;
+        }
+        false
+    }
+    if go(0, &order, sub, sup, (&sub_degs, &sup_degs), &mut emb, &mut used) { Some(emb) } else { None }
+}
+
+pub fn is_subdigraph_of(sub: &Digraph, sup: &Digraph) -> bool { find_embedding(sub, sup).is_some() }
+
+// Reference version trying every injection
+pub fn naive_is_subdigraph_of(sub: &Digraph, sup: &Digraph) -> bool {
+    use itertools::Itertools;
+    (0..sup.size).permutations(sub.size).any(|emb|
+        (0..sub.size).all(|a| (0..sub.size).all(|b| !sub.has_arc(a, b) || sup.has_arc(emb[a], emb[b]))))
+}
+
+/// All tournaments on `size` vertices up to isomorphism, in canonical form,
+/// built up a vertex at a time.
+pub fn all_tournaments(size: usize) -> BTreeSet<BitNum> {
+    let mut level = BTreeSet::new();
+    level.insert(0);
+    for n in 2..=size {
+        let mut next = BTreeSet::new();
+        for &parent in &level {
+            // Bit a of row set: the new vertex beats a
+            for row in 0..(1 as BitNum) << (n - 1) {
+                let mut arcs = parent;
+                for a in 0..n - 1 {
+                    arcs |= 1 << (arc_index(a, n - 1) + (row >> a & 1) as usize);
+                }
+                next.insert(to_best(&Digraph { size: n, arcs }).arcs);
+            }
+        }
+        level = next;
+    }
+    level
+}
+
+/// Tournaments without sub.
+pub fn noncovers<'a>(sups: impl Iterator<Item=BitNum> + 'a, sub: &'a Digraph) -> impl Iterator<Item=BitNum> + 'a {
+    sups.filter(move |&sup| !is_subdigraph_of(sub, &Digraph::from_bits(sub.size, sup)))
+}
+
+/// Looks for a tournament on sub.size vertices without sub, starting from
+/// the transitive one and reversing arcs of the copies found, as `seek::seek`
+/// adds edges of them; also returns the number of tournaments tried.
+pub fn seek(sub: &Digraph, bailout: usize) -> (Option<Digraph>, usize) {
+    let n = sub.size;
+    let mut seen = BTreeSet::new();
+    let mut stack = vec![to_best(&Digraph::transitive(n))];
+    seen.insert(stack[0].arcs);
+    while let Some(tour) = stack.pop() {
+        let Some(emb) = find_embedding(sub, &tour) else { return (Some(tour), seen.len()) };
+        for a in 0..n { for b in 0..n {
+            if !sub.has_arc(a, b) { continue }
+            let (x, y) = (emb[a], emb[b]);
+            let flipped = Digraph::from_bits(n, tour.arcs ^ (1 << arc_index(x, y)) ^ (1 << arc_index(y, x)));
+            let flipped = to_best(&flipped);
+            if seen.len() >= bailout { return (None, seen.len()) }
+            if seen.insert(flipped.arcs) { stack.push(flipped) }
+        }}
+    }
+    (None, seen.len())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::perm::all_perms;
+    use rand::Rng;
+
+    #[test]
+    fn test_digraph() {
+        // https://oeis.org/A000568
+        for (n, count) in [1, 1, 2, 4, 12, 56].into_iter().enumerate() {
+            assert_eq!(all_tournaments(n + 1).len(), count, "{}", n + 1);
+        }
+        assert!(all_tournaments(5).iter().all(|&t| Digraph::from_bits(5, t).is_tournament()));
+        let rng = &mut rand::thread_rng();
+        for _ in 0..200 {
+            let n = rng.gen_range(1..=6);
+            let dg = Digraph::from_bits(n, rng.gen_range(0..(1 as BitNum) << (n * (n - 1))));
+            let p = Perm::random(rng, n);
+            assert_eq!(to_best(&dg), to_best(&dg.unrenumber(&p)));
+            let m = rng.gen_range(1..=n);
+            let sub = Digraph::from_bits(m, rng.gen_range(0..(1 as BitNum) << (m * (m - 1))) & rng.gen::<BitNum>());
+            assert_eq!(is_subdigraph_of(&sub, &dg), naive_is_subdigraph_of(&sub, &dg), "{} {}", sub, dg);
+        }
+        // Symmetric at the largest size: twins are tried once, and fixing a
+        // vertex of a Paley tournament splits the rest
+        let empty = Digraph::from_bits(MAX_SIZE, 0);
+        assert_eq!(to_best(&empty), empty);
+        let paley = Digraph::paley(11);
+        let p = Perm::random(rng, 11);
+        assert_eq!(to_best(&paley), to_best(&paley.unrenumber(&p)));
+        let c3 = Digraph::cycle(3);
+        assert_eq!(all_perms(3).filter(|p| c3.unrenumber(p) == c3).count(), 3);
+        assert_eq!(Digraph::transitive(4).to_string(), "[0→1 0→2 1→2 0→3 1→3 2→3]");
+    }
+
+    #[test]
+    fn test_universal() {
+        let pad = |dg: Digraph, n| Digraph::from_bits(n, dg.arcs);
+        // Every tournament has a Hamiltonian path, and TT3 once n >= 4
+        for n in 2..=6 {
+            let all = all_tournaments(n);
+            assert_eq!(noncovers(all.iter().cloned(), &Digraph::path(n)).count(), 0);
+            assert_eq!(noncovers(all.iter().cloned(), &pad(Digraph::transitive(3), n)).count(), (n == 3) as usize);
+            assert_eq!(seek(&Digraph::path(n), usize::MAX).0, None);
+        }
+        // The Paley tournament on 7 vertices has no TT4
+        let tt4 = pad(Digraph::transitive(4), 7);
+        assert!(!is_subdigraph_of(&tt4, &Digraph::paley(7)));
+        let (found, _) = seek(&tt4, usize::MAX);
+        let found = found.unwrap();
+        assert!(found.is_tournament() && !is_subdigraph_of(&tt4, &found));
+        assert_eq!(to_best(&found), to_best(&Digraph::paley(7)));
+    }
+}
@@ -3,15 +3,21 @@ use crate::base::{BitNum,BitVec,Graph,Triangle,Bits};
 use crate::tools::one_bits;
 use crate::perm::Perm;
 use std::cmp::Ordering::*;
+use std::cell::Cell;
 
 struct Fixed<'a, CB: Fn(base::BitNum)> {
     pub size: usize,
     pub line: &'a mut Vec<BitNum>,
     pub callback: CB,
     pub filter: (usize, usize),
+    // Graphs come out in increasing order; these select a window of them.
+    pub skip: u64,
+    pub take: u64,
+    pub below: BitNum,
 }
 
 
+#[derive(Clone, Copy)]
 struct Recursed {
     pub at: usize,
     pub break_bits: BitNum,
@@ -238,42 +244,130 @@ fn recurse(
             }
         }
         let new_so_far = so_far | (row << offset);
+        let bound = fixed.below & !one_bits(offset);
+        if new_so_far > bound || (at == 0 && new_so_far == bound) { break }
         if at == 0 {
             if !recheck || is_best(&Graph { size: fixed.size, edges: Triangle(BitVec(new_so_far)) }) {
-                (fixed.callback)(new_so_far);
+                if fixed.skip > 0 {
+                    fixed.skip -= 1;
+                } else {
+                    (fixed.callback)(new_so_far);
+                    fixed.take -= 1;
+                    if fixed.take == 0 { return }
+                }
             }
             continue;
         }
+        let next = Recursed {
+            at: at - 1,
+            break_bits: break_bits | (row & !(row >> 1)),
+            so_far: new_so_far,
+            recheck,
+        };
         fixed.line.push(row);
-        recurse(
-            fixed,
-            Recursed {
-                at: at - 1,
-                break_bits: break_bits | (row & !(row >> 1)),
-                so_far: new_so_far,
-                recheck,
-            },
-        );
+        // Skip whole subtrees by counting them, with no callback for each graph.
+        let skipped = fixed.skip > 0 && {
+            let count = count_subtree(fixed.size, fixed.line, fixed.filter, next);
+            if count <= fixed.skip { fixed.skip -= count; true } else { false }
+        };
+        if !skipped { recurse(fixed, next) }
         fixed.line.pop();
+        if fixed.take == 0 { return }
+    }
+}
+
+fn count_subtree(size: usize, line: &mut Vec<BitNum>, filter: (usize, usize), at: Recursed) -> u64 {
+    let count = Cell::new(0);
+    recurse(
+        &mut Fixed {
+            size,
+            line,
+            callback: |_| count.set(count.get() + 1),
+            filter,
+            skip: 0,
+            take: u64::MAX,
+            below: BitNum::MAX,
+        },
+        at,
+    );
+    count.get()
+}
+
+fn root(size: usize) -> Recursed {
+    Recursed {
+        at: size - 1,
+        break_bits: 0,
+        so_far: 0,
+        recheck: false,
     }
 }
 
 pub fn enumerate_graphs(size: usize, range: Option<(usize, usize)>, callback: impl Fn(base::BitNum)) {
-    if size == 0 { return }
+    enumerate_window(size, range, 0, u64::MAX, callback);
+}
+
+// All graphs on size vertices, in increasing order
+pub fn all_graphs(size: usize) -> Vec<BitNum> {
+    let all = std::cell::RefCell::new(Vec::new());
+    enumerate_graphs(size, None, |bits| all.borrow_mut().push(bits));
+    all.into_inner()
+}
+
+// Enumeration order is increasing, so this is a slice of the sorted list.
+pub fn enumerate_window(
+    size: usize,
+    range: Option<(usize, usize)>,
+    skip: u64,
+    take: u64,
+    callback: impl Fn(base::BitNum),
+) {
+    if size == 0 || take == 0 { return }
     recurse(
         &mut Fixed {
             size,
             line: &mut Vec::with_capacity(size),
             callback,
             filter: range.unwrap_or((0, BitNum::BITS as usize)),
+            skip,
+            take,
+            below: BitNum::MAX,
         },
-        Recursed {
-            at: size - 1,
-            break_bits: 0,
-            so_far: 0,
-            recheck: false,
+        root(size),
+    );
+}
+
+pub fn count_graphs(size: usize, range: Option<(usize, usize)>) -> u64 {
+    if size == 0 { return 0 }
+    count_subtree(size, &mut Vec::with_capacity(size), range.unwrap_or((0, BitNum::BITS as usize)), root(size))
+}
+
+// Position of a canonical graph in the enumeration, if it is in it
+pub fn rank(gr: &Graph, range: Option<(usize, usize)>) -> Option<u64> {
+    let (min, max) = range.unwrap_or((0, BitNum::BITS as usize));
+    if gr.size == 0 || gr.edge_count() < min || gr.edge_count() > max || !is_best(gr) {
+        return None
+    }
+    let count = Cell::new(0);
+    recurse(
+        &mut Fixed {
+            size: gr.size,
+            line: &mut Vec::with_capacity(gr.size),
+            callback: |_| count.set(count.get() + 1),
+            filter: (min, max),
+            skip: 0,
+            take: u64::MAX,
+            below: gr.bits(),
         },
+        root(gr.size),
     );
+    Some(count.get())
+}
+
+// Graph at a given position in the enumeration
+pub fn unrank(size: usize, range: Option<(usize, usize)>, ix: u64) -> Option<BitNum> {
+    let found = Cell::new(None);
+    enumerate_window(size, range, ix, 1, |bits| found.set(Some(bits)));
+    found.get()
 }
 
 pub fn enumerate_middle(size: usize, callback: fn(base::BitNum)) {
@@ -297,6 +391,7 @@ mod tests {
     use rand::Rng;
 
     #[test]
+    #[allow(clippy::unusual_byte_groupings)]
     fn test_smoosh() {
         assert_eq!(smoosh(0b_110_01_0, 0), 0b_11_1);
         assert_eq!(smoosh(0b_110_01_0, 0b1_0), 0b_1_10_1);
@@ -313,6 +408,25 @@ mod tests {
     }
     */
 
+    #[test]
+    fn test_rank() {
+        for range in [None, Some((5, 8))] {
+            let all = std::sync::Mutex::new(Vec::new());
+            enumerate_graphs(6, range, |bits| all.lock().unwrap().push(bits));
+            let all = all.into_inner().unwrap();
+            assert!(all.windows(2).all(|w| w[0] < w[1]));
+            assert_eq!(count_graphs(6, range), all.len() as u64);
+            for (i, &bits) in all.iter().enumerate() {
+                assert_eq!(rank(&Graph::from_bits(6, bits), range), Some(i as u64));
+                assert_eq!(unrank(6, range, i as u64), Some(bits));
+            }
+            assert_eq!(unrank(6, range, all.len() as u64), None);
+            let window = std::sync::Mutex::new(Vec::new());
+            enumerate_window(6, range, 10, 7, |bits| window.lock().unwrap().push(bits));
+            assert_eq!(window.into_inner().unwrap(), &all[10..17]);
+        }
+    }
+
     #[test]
     fn test_best() {
         let rng = &mut rand::thread_rng();
@@ -0,0 +1,109 @@
+/*!
+    Monte Carlo estimates of how often random graphs on n vertices escape a
+    target, for n too large to check every graph.
+
+    Sample i is drawn with its own generator seeded from the seed and i, so
+    a run is repeatable whatever the number of threads, and mixed so that
+    nearby seeds share no samples.
+*/
+
+use crate::base::Graph;
+use crate::tools::Target;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+use rayon::prelude::*;
+
+// Samples checked in parallel at a time
+const BLOCK: usize = 10_000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Estimate {
+    pub samples: usize,
+    pub failures: usize,
+    // The first failures, in sample order
+    pub counterexamples: Vec<Graph>,
+}
+
+impl Estimate {
+    pub fn rate(&self) -> f64 {
+        if self.samples == 0 { 0.0 } else { self.failures as f64 / self.samples as f64 }
+    }
+    /// Wilson score interval for the failure rate, z = 1.96 giving 95%.
+    pub fn wilson(&self, z: f64) -> (f64, f64) {
+        if self.samples == 0 { return (0.0, 1.0) }
+        let n = self.samples as f64;
+        let p = self.rate();
+        let z2 = z * z;
+        let centre = (p + z2 / (2.0 * n)) / (1.0 + z2 / n);
+        let half = z / (1.0 + z2 / n) * (p * (1.0 - p) / n + z2 / (4.0 * n * n)).sqrt();
+        ((centre - half).max(0.0), (centre + half).min(1.0))
+    }
+}
+
+// SplitMix64 finaliser applied to the seed, then with i, so that (s, i + 1)
+// and (s + 1, i) differ
+fn sample_seed(seed: u64, i: u64) -> u64 {
+    let mix = |x: u64| {
+        let x = x.wrapping_add(0x9e3779b97f4a7c15);
+        let x = (x ^ (x >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        let x = (x ^ (x >> 27)).wrapping_mul(0x94d049bb133111eb);
+        x ^ (x >> 31)
+    };
+    mix(mix(seed) ^ i)
+}
+
+/// Checks `samples` graphs from `sample` against the target, keeping the
+/// first `keep` that it does not cover.
+pub fn estimate(target: &Target, samples: usize, seed: u64, keep: usize,
+        sample: impl Fn(&mut StdRng) -> Graph + Sync) -> Estimate {
+    let mut est = Estimate { samples, failures: 0, counterexamples: Vec::new() };
+    for start in (0..samples).step_by(BLOCK) {
+        let fails: Vec<Graph> = (start..samples.min(start + BLOCK)).into_par_iter().filter_map(|i| {
+            let rng = &mut StdRng::seed_from_u64(sample_seed(seed, i as u64));
+            let gr = sample(rng);
+            if target.covers(&gr) { None } else { Some(gr) }
+        }).collect();
+        est.failures += fails.len();
+        let room = keep - est.counterexamples.len().min(keep);
+        est.counterexamples.extend(fails.into_iter().take(room));
+    }
+    est
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::base::{random_graph,Bits};
+    use crate::{enumerate,families};
+
+    #[test]
+    fn test_estimate() {
+        let est = |failures, samples| Estimate { samples, failures, counterexamples: Vec::new() };
+        let (lo, hi) = est(0, 100).wilson(1.96);
+        assert_eq!(lo, 0.0);
+        assert!((hi - 0.0370).abs() < 1e-4, "{}", hi);
+        let (lo, hi) = est(5, 10).wilson(1.96);
+        assert!((lo + hi - 1.0).abs() < 1e-9 && (lo - 0.2366).abs() < 1e-4, "{} {}", lo, hi);
+        // Of the 1024 labelled graphs on 5 vertices, only the 12 labelled
+        // C5s have no triangle in them or their complements
+        let k3 = Graph::from_bits(5, 7);
+        let k3 = Target::pair(&k3, &k3);
+        let run = |seed| estimate(&k3, 3000, seed, 3, |rng| random_graph(rng, 5));
+        let first = run(5);
+        assert_eq!(first, run(5));
+        // At 99.9%, as the seed is fixed
+        let (lo, hi) = first.wilson(3.29);
+        assert!(lo < 12.0 / 1024.0 && 12.0 / 1024.0 < hi, "{:?}", first);
+        assert_eq!(first.counterexamples.len(), 3);
+        for ce in &first.counterexamples {
+            assert_eq!(enumerate::to_best(ce), enumerate::to_best(&families::cycle(5)));
+        }
+        // Nearby seeds give different runs
+        let seeds: Vec<u64> = (0..4).flat_map(|s| (0..4).map(move |i| sample_seed(s, i))).collect();
+        assert!(seeds.iter().all(|a| seeds.iter().filter(|&b| a == b).count() == 1));
+        // R(3, 3) = 6
+        let k3 = Graph::from_bits(6, 7);
+        let k3 = Target::pair(&k3, &k3);
+        assert_eq!(estimate(&k3, 500, 1, 3, |rng| random_graph(rng, 6)).failures, 0);
+    }
+}
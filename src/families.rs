@@ -0,0 +1,361 @@
+/*!
+    Named graphs and graph families, and a parser for writing them as
+    expressions like `cycle(7)` or `paley(13)` wherever a graph is expected.
+    Expressions can also combine graphs, as in `join(cycle(5), 1)`.
+*/
+
+use crate::base::{BitNum,Graph,Bits,MAX_SIZE};
+use crate::tools;
+
+pub fn empty(n: usize) -> Graph { Graph::from_fn(n, |_, _| false) }
+
+pub fn complete(n: usize) -> Graph { Graph::from_fn(n, |_, _| true) }
+
+pub fn path(n: usize) -> Graph { Graph::from_fn(n, |a, b| b == a + 1) }
+
+pub fn cycle(n: usize) -> Graph { circulant(n, &[1]) }
+
+// K_{1,k}, centre 0
+pub fn star(k: usize) -> Graph { Graph::from_fn(k + 1, |a, _| a == 0) }
+
+// k disjoint edges
+pub fn matching(k: usize) -> Graph { Graph::from_fn(2 * k, |a, b| a % 2 == 0 && b == a + 1) }
+
+pub fn complete_bipartite(a: usize, b: usize) -> Graph { complete_multipartite(&[a, b]) }
+
+pub fn complete_multipartite(parts: &[usize]) -> Graph {
+    let part: Vec<usize> = parts.iter().enumerate()
+        .flat_map(|(i, &len)| std::iter::repeat_n(i, len)).collect();
+    Graph::from_fn(part.len(), |a, b| part[a] != part[b])
+}
+
+// Complete r-partite graph with parts as equal as possible
+pub fn turan(n: usize, r: usize) -> Graph {
+    let parts: Vec<usize> = (0..r).map(|i| n / r + usize::from(i < n % r)).collect();
+    complete_multipartite(&parts)
+}
+
+// a ~ b when b - a is ± some jump, mod n
+pub fn circulant(n: usize, jumps: &[usize]) -> Graph {
+    Graph::from_fn(n, |a, b| jumps.iter().any(|&j| j % n != 0 && (b - a == j % n || b - a == n - j % n)))
+}
+
+pub fn petersen() -> Graph { kneser(5, 2) }
+
+// Vertices are k-subsets of an n-set, adjacent when disjoint
+pub fn kneser(n: usize, k: usize) -> Graph {
+    let sets: Vec<usize> = (0usize .. 1 << n).filter(|s| s.count_ones() as usize == k).collect();
+    Graph::from_fn(sets.len(), |a, b| sets[a] & sets[b] == 0)
+}
+
+// Residues mod a prime q ≡ 1 (mod 4), adjacent when they differ by a square
+pub fn paley(q: usize) -> Graph {
+    let squares: Vec<usize> = (1..q).map(|x| x * x % q).collect();
+    Graph::from_fn(q, |a, b| squares.contains(&(b - a)))
+}
+
+// Hub 0 joined to a cycle on the other n - 1 vertices
+pub fn wheel(n: usize) -> Graph {
+    let rim = cycle(n - 1);
+    Graph::from_fn(n, |a, b| a == 0 || rim.has_edge(a - 1, b - 1))
+}
+
+// k triangles sharing vertex 0
+pub fn friendship(k: usize) -> Graph {
+    Graph::from_fn(2 * k + 1, |a, b| a == 0 || (a % 2 == 1 && b == a + 1))
+}
+
+fn is_prime(q: usize) -> bool { q >= 2 && (2..q).take_while(|d| d * d <= q).all(|d| !q.is_multiple_of(d)) }
+
+fn binomial(n: usize, k: usize) -> usize {
+    if k > n { return 0 }
+    (0..k).fold(1, |acc, i| acc * (n - i) / (i + 1))
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    Num(BitNum),
+    Call(String, Vec<Expr>),
+}
+
+struct Parser<'a> { rest: &'a str }
+
+impl Parser<'_> {
+    fn skip_space(&mut self) { self.rest = self.rest.trim_start() }
+    fn eat(&mut self, c: char) -> bool {
+        self.skip_space();
+        match self.rest.strip_prefix(c) {
+            Some(rest) => { self.rest = rest; true },
+            None => false,
+        }
+    }
+    fn token(&mut self, f: impl Fn(char) -> bool) -> &str {
+        self.skip_space();
+        let end = self.rest.find(|c| !f(c)).unwrap_or(self.rest.len());
+        let (tok, rest) = self.rest.split_at(end);
+        self.rest = rest;
+        tok
+    }
+    fn expr(&mut self) -> Result<Expr, String> {
+        let num = self.token(|c| c.is_ascii_digit());
+        if !num.is_empty() {
+            return num.parse().map(Expr::Num).map_err(|e| format!("{}: {}", num, e))
+        }
+        let name = self.token(|c| c.is_ascii_alphanumeric() || c == '_').to_string();
+        if name.is_empty() { return Err(format!("Expected a graph at '{}'", self.rest)) }
+        let mut args = Vec::new();
+        if self.eat('(') && !self.eat(')') {
+            loop {
+                args.push(self.expr()?);
+                if self.eat(')') { break }
+                if !self.eat(',') { return Err(format!("Expected ',' or ')' at '{}'", self.rest)) }
+            }
+        }
+        Ok(Expr::Call(name, args))
+    }
+}
+
+pub fn parse_expr(s: &str) -> Result<Expr, String> {
+    let mut parser = Parser { rest: s };
+    let expr = parser.expr()?;
+    parser.skip_space();
+    if !parser.rest.is_empty() { return Err(format!("Unexpected '{}'", parser.rest)) }
+    Ok(expr)
+}
+
+fn int(expr: &Expr) -> Result<usize, String> {
+    match expr {
+        Expr::Num(n) => usize::try_from(*n).map_err(|e| e.to_string()),
+        _ => Err(format!("Expected a number, got {:?}", expr)),
+    }
+}
+
+fn fits(n: usize) -> Result<usize, String> {
+    if n > MAX_SIZE { Err(format!("{} vertices is more than {}", n, MAX_SIZE)) } else { Ok(n) }
+}
+
+// A vertex count worked out from the arguments, None if it overflowed
+fn fits_checked(n: Option<usize>) -> Result<usize, String> {
+    fits(n.ok_or_else(|| format!("More than {} vertices", MAX_SIZE))?)
+}
+
+fn vertex(gr: &Graph, expr: &Expr) -> Result<usize, String> {
+    let v = int(expr)?;
+    if v < gr.size { Ok(v) } else { Err(format!("No vertex {} in {}", v, gr)) }
+}
+
+// Operations on graphs; None if name is not one
+fn eval_op(name: &str, args: &[Expr]) -> Result<Option<Graph>, String> {
+    let arity = |n: usize| if args.len() == n { Ok(()) } else {
+        Err(format!("{} takes {} argument{}", name, n, if n == 1 { "" } else { "s" }))
+    };
+    let first = || match args.first() {
+        Some(expr) => eval(expr),
+        None => Err(format!("{} needs a graph", name)),
+    };
+    let too_big = || format!("{} gives more than {} vertices", name, MAX_SIZE);
+    Ok(Some(match name {
+        "graph" => {
+            arity(2)?;
+            let bits = match args[1] { Expr::Num(bits) => bits, _ => return Err("graph takes a number".into()) };
+            let gr = Graph::from_bits(fits(int(&args[0])?)?, bits);
+            if bits != 0 && tools::infer_size(bits) > gr.size { return Err(format!("{} has too many vertices", bits)) }
+            gr
+        },
+        "complement" => { arity(1)?; first()?.complement() },
+        "line" => { arity(1)?; first()?.line_graph().ok_or_else(too_big)? },
+        "union" | "join" | "cartesian" | "tensor" | "strong" | "lex" => {
+            arity(2)?;
+            let (g, h) = (first()?, eval(&args[1])?);
+            match name {
+                "union" => g.disjoint_union(&h),
+                "join" => g.join(&h),
+                "cartesian" => g.cartesian_product(&h),
+                "tensor" => g.tensor_product(&h),
+                "strong" => g.strong_product(&h),
+                _ => g.lexicographic_product(&h),
+            }.ok_or_else(too_big)?
+        },
+        "delete" => { arity(2)?; let gr = first()?; gr.delete_vertex(vertex(&gr, &args[1])?) },
+        "add" => {
+            let gr = first()?;
+            let nbrs = args[1..].iter().map(|x| vertex(&gr, x)).collect::<Result<Vec<_>, _>>()?;
+            gr.add_vertex(&nbrs).ok_or_else(too_big)?
+        },
+        "contract" => {
+            arity(3)?;
+            let gr = first()?;
+            let (a, b) = (vertex(&gr, &args[1])?, vertex(&gr, &args[2])?);
+            if !gr.has_edge(a, b) { return Err(format!("No edge {}–{} in {}", a, b, gr)) }
+            gr.contract_edge(a, b)
+        },
+        "induced" => {
+            let gr = first()?;
+            let vs = args[1..].iter().map(|x| vertex(&gr, x)).collect::<Result<Vec<_>, _>>()?;
+            if let Some(i) = (1..vs.len()).find(|&i| vs[..i].contains(&vs[i])) {
+                return Err(format!("Vertex {} repeated", vs[i]))
+            }
+            gr.induced_subgraph(&vs)
+        },
+        _ => return Ok(None),
+    }))
+}
+
+// A bare number is a graph given by its bits, with vertices up to the last
+// one with an edge.
+pub fn eval(expr: &Expr) -> Result<Graph, String> {
+    let (name, args) = match expr {
+        Expr::Num(0) => return Err("Use empty(n) or graph(n, 0) for an empty graph".into()),
+        Expr::Num(bits) => {
+            if tools::hi_bit_ix(*bits) >= Graph::triangle(MAX_SIZE) {
+                return Err(format!("{} has too many vertices", bits))
+            }
+            return Ok(tools::infer_graph(*bits))
+        },
+        Expr::Call(name, args) => (name.as_str(), args),
+    };
+    if let Some(gr) = eval_op(name, args)? { return Ok(gr) }
+    let ints = args.iter().map(int).collect::<Result<Vec<_>, _>>()?;
+    let arity = |n: usize| if ints.len() == n { Ok(()) } else {
+        Err(format!("{} takes {} argument{}", name, n, if n == 1 { "" } else { "s" }))
+    };
+    Ok(match name {
+        "empty" => { arity(1)?; empty(fits(ints[0])?) },
+        "complete" => { arity(1)?; complete(fits(ints[0])?) },
+        "path" => { arity(1)?; path(fits(ints[0])?) },
+        "cycle" => { arity(1)?; cycle(fits(ints[0])?) },
+        "star" => { arity(1)?; fits_checked(ints[0].checked_add(1))?; star(ints[0]) },
+        "matching" => { arity(1)?; fits_checked(ints[0].checked_mul(2))?; matching(ints[0]) },
+        "bipartite" => { arity(2)?; fits_checked(ints[0].checked_add(ints[1]))?; complete_bipartite(ints[0], ints[1]) },
+        "multipartite" => {
+            fits_checked(ints.iter().try_fold(0usize, |n, &part| n.checked_add(part)))?;
+            complete_multipartite(&ints)
+        },
+        "turan" => {
+            arity(2)?;
+            if ints[1] == 0 { return Err("turan needs at least one part".into()) }
+            turan(fits(ints[0])?, ints[1])
+        },
+        "circulant" => {
+            if ints.is_empty() { return Err("circulant needs a size".into()) }
+            circulant(fits(ints[0])?, &ints[1..])
+        },
+        "petersen" => { arity(0)?; petersen() },
+        "kneser" => {
+            arity(2)?;
+            fits(ints[0])?;
+            fits(binomial(ints[0], ints[1]))?;
+            kneser(ints[0], ints[1])
+        },
+        "paley" => {
+            arity(1)?;
+            let q = fits(ints[0])?;
+            if !is_prime(q) || q % 4 != 1 {
+                return Err("paley needs a prime ≡ 1 (mod 4)".into())
+            }
+            paley(q)
+        },
+        "wheel" => {
+            arity(1)?;
+            if ints[0] < 4 { return Err("wheel needs at least 4 vertices".into()) }
+            wheel(fits(ints[0])?)
+        },
+        "friendship" => { arity(1)?; fits_checked(ints[0].checked_mul(2).and_then(|n| n.checked_add(1)))?; friendship(ints[0]) },
+        _ => return Err(format!("Unknown graph '{}'", name)),
+    })
+}
+
+pub fn parse(s: &str) -> Result<Graph, String> { eval(&parse_expr(s)?) }
+
+/// A graph argument: either bits as a number or a family expression, which
+/// also gives its number of vertices.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GraphArg { pub bits: BitNum, pub size: Option<usize> }
+
+impl GraphArg {
+    /// The bits for graphs on `size` vertices, which must be at least as many
+    /// as the family has; fewer vertices are padded with isolated ones.
+    pub fn on(self, size: usize) -> Result<BitNum, String> {
+        match self.size {
+            Some(n) if n > size => Err(format!("The graph has {} vertices, more than {}", n, size)),
+            _ if self.bits != 0 && tools::infer_size(self.bits) > size =>
+                Err(format!("{} has more than {} vertices", self.bits, size)),
+            _ => Ok(self.bits),
+        }
+    }
+    /// The graph as given: a family on its own vertices, and bits on the
+    /// vertices up to the last with an edge.
+    pub fn graph(self) -> Graph {
+        match self.size {
+            Some(size) => Graph::from_bits(size, self.bits),
+            None => tools::infer_graph(self.bits),
+        }
+    }
+}
+
+pub fn parse_bits(s: &str) -> Result<GraphArg, String> {
+    match s.trim().parse::<BitNum>() {
+        Ok(bits) => Ok(GraphArg { bits, size: None }),
+        Err(_) => parse(s).map(|gr| GraphArg { bits: gr.bits(), size: Some(gr.size) }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enumerate::to_best;
+
+    fn regular(gr: &Graph) -> Option<usize> {
+        let d = gr.degree_of(0);
+        (0..gr.size).all(|v| gr.degree_of(v) == d).then_some(d)
+    }
+
+    #[test]
+    fn test_families() {
+        assert_eq!(complete(6).edge_count(), 15);
+        assert_eq!(path(6).edge_count(), 5);
+        assert_eq!(regular(&cycle(7)), Some(2));
+        assert_eq!(star(4).degree_of(0), 4);
+        assert_eq!(regular(&matching(4)), Some(1));
+        assert_eq!(complete_bipartite(3, 4).edge_count(), 12);
+        assert_eq!(turan(6, 3), complete_multipartite(&[2, 2, 2]));
+        assert_eq!(turan(7, 3).edge_count(), 16);
+        assert_eq!(regular(&petersen()), Some(3));
+        assert_eq!(petersen().edge_count(), 15);
+        assert_eq!(to_best(&paley(5)), to_best(&cycle(5)));
+        assert_eq!(regular(&paley(13)), Some(6));
+        assert_eq!(regular(&circulant(8, &[1, 4])), Some(3));
+        assert_eq!(wheel(6).edge_count(), 10);
+        assert_eq!(friendship(3).edge_count(), 9);
+        assert_eq!(to_best(&kneser(4, 1)), complete(4));
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(parse("cycle(7)"), Ok(cycle(7)));
+        assert_eq!(parse(" multipartite( 1, 2,3 ) "), Ok(complete_multipartite(&[1, 2, 3])));
+        assert_eq!(parse("petersen"), Ok(petersen()));
+        assert_eq!(parse_bits("94").and_then(|arg| arg.on(5)), Ok(94));
+        assert_eq!(parse_bits("path(3)").and_then(|arg| arg.on(3)), Ok(0b101));
+        assert_eq!(parse_bits("path(3)").and_then(|arg| arg.on(6)), Ok(0b101));
+        assert!(parse_bits("94").and_then(|arg| arg.on(4)).is_err());
+        assert!(parse_bits("empty(6)").and_then(|arg| arg.on(5)).is_err());
+        assert_eq!(parse("join(cycle(5), complete(1))").map(|g| to_best(&g)), Ok(to_best(&wheel(6))));
+        assert_eq!(parse("line(complete(3))").map(|g| to_best(&g)), Ok(complete(3)));
+        assert_eq!(parse("cartesian(path(2), path(2))").map(|g| g.edge_count()), Ok(4));
+        assert_eq!(parse("delete(94, 4)").map(|g| g.edge_count()), Ok(4));
+        assert_eq!(parse("add(complete(3), 0, 1)").map(|g| g.edge_count()), Ok(5));
+        assert_eq!(parse("induced(petersen, 0, 1, 2, 3, 4)").map(|g| g.size), Ok(5));
+        assert!(parse("induced(cycle(5), 1, 1)").is_err());
+        assert_eq!(parse("contract(cycle(4), 0, 1)"), Ok(complete(3)));
+        assert_eq!(parse("graph(6, 94)").map(|g| g.size), Ok(6));
+        let too_big = (usize::MAX / 2 + 1).to_string();
+        for expr in ["star(N)", "matching(N)", "bipartite(N, N)", "multipartite(N, N, 1)", "friendship(N)", "paley(N)"] {
+            assert!(parse(&expr.replace('N', &too_big)).is_err(), "{}", expr);
+        }
+        for bad in ["cycle(17)", "paley(7)", "kneser(7, 3)", "cycle(3", "cycle(3, 4)", "hat(3)", "cycle(x)",
+                "union(complete(9), complete(9))", "contract(path(3), 0, 2)", "delete(94, 5)", "graph(3, 94)"] {
+            assert!(parse(bad).is_err(), "{}", bad);
+        }
+    }
+}
@@ -5,6 +5,19 @@ pub mod tools;
 pub mod enumerate;
 pub mod seek;
 pub mod progress;
+pub mod selfcomp;
+pub mod augment;
+pub mod random;
+pub mod families;
+pub mod cert;
+pub mod catalog;
+pub mod threshold;
+pub mod ramsey;
+pub mod class;
+pub mod colour;
+pub mod digraph;
+pub mod estimate;
+pub mod poset;
 
 use base::{Graph, BitNum,Bits};
 use std::collections::BTreeSet;
@@ -25,6 +38,15 @@ pub fn read<B: Bits>(size: usize) -> Vec<B> {
     x
 }
 
+// From the file if there is one, otherwise enumerated
+pub fn all_graphs(size: usize) -> Vec<BitNum> {
+    if std::path::Path::new(&format!("output/all{}.txt", size)).exists() {
+        read(size)
+    } else {
+        enumerate::all_graphs(size)
+    }
+}
+
 // Dummy function for experimentation
 pub fn run_graphs(_size: usize) {
     // println!("{:?}", seek::seek(&Graph::from_bits(5, 30)));
@@ -47,8 +69,13 @@ fn stats(path: String) {
     }
 }
 
-fn ingraph_scan(size: usize, pool: impl Iterator<Item=Graph>) {
-    let all = read::<BitNum>(size);
+// With co, checks each graph paired with it rather than with itself
+fn ingraph_scan(size: usize, pool: impl Iterator<Item=Graph>, co: Option<BitNum>, induced: Option<usize>,
+        class: Option<class::Class>) {
+    let all = match &class {
+        Some(class) => class::graphs(size, class),
+        None => read::<BitNum>(size),
+    };
     // let mut count = 0;
     let progress = progress::Progress::new();
         // |(i, celen, val)| format!("{} {} ({})", i, celen, val));
@@ -57,10 +84,12 @@ fn ingraph_scan(size: usize, pool: impl Iterator<Item=Graph>) {
         let val = gr.bits();
         let ec = val.count_ones();
         progress.tick(|| format!("{} {} ({})", i, counterexamples.len(), val));
-        let chkce = tools::noncovers(counterexamples.iter().cloned(), &gr).next();
+        let co = co.map_or_else(|| gr.clone(), |co| Graph::from_bits(size, co));
+        let target = tools::Target::pair(&gr, &co).with_induced(induced_arg(induced, size, &[&gr, &co]));
+        let chkce = noncover(counterexamples.iter().cloned(), target.clone(), class.as_ref());
         let counter =
             chkce.or_else(|| {
-                let seek = tools::noncovers(all.iter().cloned(), &gr).next();
+                let seek = noncover(all.iter().cloned(), target, class.as_ref());
                 if let Some(c) = seek { counterexamples.insert(c); }
                 seek
             });
@@ -76,7 +105,22 @@ fn ingraph_scan(size: usize, pool: impl Iterator<Item=Graph>) {
     }
 }
 
-fn ingraph_seek(pool: impl Iterator<Item=Graph>, bailout: usize) {
+// The first graph among sups in the class, if any, that the target does not cover
+fn noncover(sups: impl Iterator<Item=BitNum>, target: tools::Target, class: Option<&class::Class>) -> Option<BitNum> {
+    match class {
+        Some(class) => class::noncovers(sups, target, class).next(),
+        None => tools::noncovers_target(sups, target).next(),
+    }
+}
+
+// Each target is an ingraph, a pair or a family
+fn ingraph_seek(pool: impl Iterator<Item=tools::Target>, bailout: usize, class: Option<&class::Class>) {
+    // Seeking only adds edges, so it cannot keep to other classes
+    if class.is_some_and(|class| !class.is_monotone()) {
+        clap::Error::raw(clap::error::ErrorKind::InvalidValue,
+            "Seeking needs a class closed under removing edges: triangle-free, bipartite or planar\n").exit()
+    }
+    let contains = class.map(|class| move |gr: &Graph| class.contains(gr));
     let progress = progress::Progress::new();
     let mut counterexamples: BTreeSet<_> = [
         /* can be pre-seeded with known good counterexamples
@@ -87,19 +131,17 @@ fn ingraph_seek(pool: impl Iterator<Item=Graph>, bailout: usize) {
         541524869842467840,
         */
     ].iter().map(|ce| (0, *ce)).collect();
-    for (i, gr) in pool.enumerate() {
-        let val = gr.bits();
-        let ec = val.count_ones();
+    for (i, target) in pool.enumerate() {
+        let val = target.subs[0].0.bits();
         progress.tick(|| format!("{} {} ({})", i, counterexamples.len(), val));
         // A lot of hacky stuff here was trying to find "better" counterexamples.
         // let chkce = tools::noncovers(counterexamples.iter().map(|(_, x)| *x), &gr).next();
         let chkce = {
-            let sub_sorted = tools::build_sorted_row(&gr);
             let mut ans = None;
             let vec: Vec<_> = counterexamples.iter().rev().cloned().collect();
             for el@(_, sup) in vec {
-                let sup = Graph::from_bits(gr.size, sup);
-                if !tools::ingraph_check(&sup, &sub_sorted, &gr) {
+                let sup = Graph::from_bits(target.size, sup);
+                if !target.covers(&sup) {
                     counterexamples.remove(&el);
                     counterexamples.insert((i, sup.bits()));
                     // counterexamples.insert((ce_score(&gr1), gr1.bits()));
@@ -112,7 +154,7 @@ fn ingraph_seek(pool: impl Iterator<Item=Graph>, bailout: usize) {
         let counter =
             chkce.map_or_else(
                 || {
-                    let seek = crate::seek::seek(&gr, bailout);
+                    let seek = crate::seek::seek_target(&target, bailout, contains.as_ref().map(|f| f as seek::Within));
                     if let Some(gr1) = seek.0.clone() {
                         counterexamples.insert((i, gr1.bits()));
                         // counterexamples.insert((ce_score(&gr1), gr1.bits()));
@@ -122,20 +164,73 @@ fn ingraph_seek(pool: impl Iterator<Item=Graph>, bailout: usize) {
                 |x| (Some(x), 0),
             );
         // if counter.is_some() { continue }
-        println!("{:?},{},{:?},{:?},{}",
-            val,
-            gr,
-            // tools::count_symmetries(&gr),
-            ec,
+        println!("{},{:?},{}",
+            target_label(&target),
             counter,
             tools::timestamp(),
         );
     }
 }
 
-fn ingraph_check(sub: &Graph, list: impl Iterator<Item=Graph>) -> Option<Graph> {
-    let sub_sorted = tools::build_sorted_row(sub);
-    list.into_iter().find(|sup| !tools::ingraph_check(sup, &sub_sorted, sub))
+// As ingraph_scan, for digraphs against all tournaments
+fn directed_scan(size: usize, pool: impl Iterator<Item=digraph::Digraph>) {
+    let all = digraph::all_tournaments(size);
+    eprintln!("Tournaments: {:?}", all.len());
+    let progress = progress::Progress::new();
+    let mut counterexamples = BTreeSet::new();
+    for (i, dg) in pool.enumerate() {
+        let val = dg.bits();
+        progress.tick(|| format!("{} {} ({})", i, counterexamples.len(), val));
+        let chkce = digraph::noncovers(counterexamples.iter().cloned(), &dg).next();
+        let counter = chkce.or_else(|| {
+            let seek = digraph::noncovers(all.iter().cloned(), &dg).next();
+            if let Some(c) = seek { counterexamples.insert(c); }
+            seek
+        });
+        println!("{:?},{},{:?},{:?},{}", val, dg, dg.arc_count(), counter, tools::timestamp());
+    }
+}
+
+// As ingraph_seek, for digraphs against tournaments
+fn directed_seek(pool: impl Iterator<Item=digraph::Digraph>, bailout: usize) {
+    let progress = progress::Progress::new();
+    let mut counterexamples: Vec<BitNum> = Vec::new();
+    for (i, dg) in pool.enumerate() {
+        let val = dg.bits();
+        progress.tick(|| format!("{} {} ({})", i, counterexamples.len(), val));
+        let chkce = digraph::noncovers(counterexamples.iter().rev().cloned(), &dg).next();
+        let counter = match chkce {
+            Some(ce) => (Some(ce), 0),
+            None => {
+                let (found, tried) = digraph::seek(&dg, bailout);
+                let found = found.map(|t| t.bits());
+                counterexamples.extend(found);
+                (found, tried)
+            }
+        };
+        println!("{},{},{},{:?},{}", val, dg, dg.arc_count(), counter, tools::timestamp());
+    }
+}
+
+// The graph, its drawing and edge count; for several graphs, their numbers
+fn target_label(target: &tools::Target) -> String {
+    match &target.subs[..] {
+        [(gr, _)] => format!("{},{},{}", gr.bits(), gr, gr.bits().count_ones()),
+        subs => subs.iter().map(|(gr, _)| gr.bits().to_string()).collect::<Vec<_>>().join(" "),
+    }
+}
+
+fn ingraph_check(target: &tools::Target, list: impl Iterator<Item=Graph>, cert: Option<&mut cert::Writer>)
+        -> Option<Graph> {
+    match cert {
+        None => list.into_iter().find(|sup| !target.covers(sup)),
+        Some(cert) => list.into_iter().find(|sup| {
+            let ((sub, sub_sorted), (co, co_sorted)) = (&target.subs[0], &target.cos[0]);
+            let emb = cert::find_embedding(sup, sub_sorted, sub, co_sorted, co);
+            cert.add(sup.bits(), &emb);
+            emb.is_none()
+        }),
+    }
 }
 
 // Counts: modulo complements and symmetries, modulo symmetries, "labelled"
@@ -153,7 +248,7 @@ fn miss_counts(gr: &Graph) -> (usize, usize, usize) {
         // eprintln!("{} {}", gr1, syms);
         if match half { Some(x) => x == gr1.edge_count(), _ => false } {
             counts.0 +=
-                if tools::is_subgraph_of(&gr1, &gr1.complement()) { 2 } else { 1 };
+                if selfcomp::is_self_complementary(&gr1).is_some() { 2 } else { 1 };
             counts.1 += 1;
             counts.2 += syms;
         } else {
@@ -197,6 +292,9 @@ enum C {
     Enumerate {
         /// Number of vertices
         size: usize,
+        /// Enumerate tournaments instead
+        #[arg(long)]
+        directed: bool,
     },
     /// Enumerate all graphs in a range of edge counts
     EnumerateFilter {
@@ -206,23 +304,135 @@ enum C {
         min: usize,
         /// Maximum number of edges
         max: usize,
+        /// Skip this many graphs first, by counting whole subtrees of them
+        #[arg(long, default_value_t = 0)]
+        skip: u64,
+        /// Stop after this many graphs
+        #[arg(long)]
+        take: Option<u64>,
+    },
+    /// Position of a graph in the enumeration
+    Rank {
+        /// Number of vertices
+        size: usize,
+        /// Graph
+        #[arg(value_parser = families::parse_bits)]
+        bits: families::GraphArg,
+        /// Minimum number of edges
+        #[arg(long)]
+        min: Option<usize>,
+        /// Maximum number of edges
+        #[arg(long)]
+        max: Option<usize>,
+    },
+    /// Graph at a position in the enumeration
+    Unrank {
+        /// Number of vertices
+        size: usize,
+        /// Position, counting from 0
+        index: u64,
+        /// Minimum number of edges
+        #[arg(long)]
+        min: Option<usize>,
+        /// Maximum number of edges
+        #[arg(long)]
+        max: Option<usize>,
     },
     /// Enumerate all graphs with half the possible edges modulo complements
     EnumerateMiddle {
         /// Number of vertices
         size: usize,
     },
+    /// Enumerate all self-complementary graphs
+    SelfComplementary {
+        /// Number of vertices
+        size: usize,
+    },
+    /// Compare the orderly enumeration with canonical augmentation
+    CrossCheck {
+        /// Maximum number of vertices
+        max: usize,
+    },
+    /// Random graphs, in canonical form
+    Random {
+        /// Number of vertices
+        size: usize,
+        /// Number of graphs
+        #[arg(long, default_value_t = 1)]
+        count: usize,
+        /// Random seed
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Random graph model
+        #[arg(long, value_enum, default_value_t = Model::Unlabelled)]
+        model: Model,
+        /// Number of edges (unlabelled, gnm)
+        #[arg(long)]
+        edges: Option<usize>,
+        /// Edge probability (gnp)
+        #[arg(long, default_value_t = 0.5, value_parser = parse_probability)]
+        p: f64,
+        /// Vertex degree (regular)
+        #[arg(long)]
+        degree: Option<usize>,
+    },
+    /// Estimate the fraction of random graphs without a graph or in its complement
+    Estimate {
+        /// Number of vertices
+        size: usize,
+        /// Graph
+        #[arg(value_parser = families::parse_bits)]
+        bits: families::GraphArg,
+        /// Look for this graph in complements instead
+        #[arg(long, value_parser = families::parse_bits)]
+        co: Option<families::GraphArg>,
+        /// Number of graphs sampled
+        #[arg(long, default_value_t = 100_000)]
+        samples: usize,
+        /// Random seed
+        #[arg(long)]
+        seed: Option<u64>,
+        /// Random graph model
+        #[arg(long, value_enum, default_value_t = Model::Labelled)]
+        model: Model,
+        /// Number of edges (unlabelled, gnm)
+        #[arg(long)]
+        edges: Option<usize>,
+        /// Edge probability (gnp)
+        #[arg(long, default_value_t = 0.5, value_parser = parse_probability)]
+        p: f64,
+        /// Vertex degree (regular)
+        #[arg(long)]
+        degree: Option<usize>,
+        /// Number of counterexamples shown
+        #[arg(long, default_value_t = 5)]
+        show: usize,
+    },
     /// List all graphs with one fewer edge
     Retract {
         /// Graph to retract
-        bits: BitNum,
+        #[arg(value_parser = families::parse_bits)]
+        bits: families::GraphArg,
+        /// Remove a vertex instead
+        #[arg(long, conflicts_with = "depth")]
+        vertex: bool,
+        /// Number of edges to remove
+        #[arg(long, default_value_t = 1)]
+        depth: usize,
     },
     /// List all graphs with one more edge
     Extend {
         /// Number of vertices
         size: usize,
         /// Graph to extend
-        bits: BitNum,
+        #[arg(value_parser = families::parse_bits)]
+        bits: families::GraphArg,
+        /// Add a vertex instead
+        #[arg(long, conflicts_with = "depth")]
+        vertex: bool,
+        /// Number of edges to add
+        #[arg(long, default_value_t = 1)]
+        depth: usize,
     },
     /// Stats on number of graphs per edge count in a file
     // Can check against https://oeis.org/A008406/b008406.txt
@@ -235,7 +445,11 @@ enum C {
         /// Number of vertices
         size: usize,
         /// Key graphs
-        bits: Vec<BitNum>,
+        #[arg(value_parser = families::parse_bits)]
+        bits: Vec<families::GraphArg>,
+        /// List every counterexample, by edge count, instead of counting
+        #[arg(long)]
+        list: bool,
     },
     /// Scan a file of graphs for ingraphs
     // Results include counterexamples; grep None for ingraphs
@@ -244,6 +458,18 @@ enum C {
         size: usize,
         /// Graphs file
         path: String,
+        /// Look for this graph in complements instead
+        #[arg(long, value_parser = families::parse_bits)]
+        co: Option<families::GraphArg>,
+        /// Look for induced subgraphs on this many vertices, the graph's first
+        #[arg(long, value_name = "VERTICES")]
+        induced: Option<usize>,
+        /// Only count graphs in this class, such as planar or degrees(2,2,2,2)
+        #[arg(long)]
+        class: Option<class::Class>,
+        /// Digraphs in tournaments rather than graphs in graphs
+        #[arg(long, conflicts_with_all = ["co", "induced", "class"])]
+        directed: bool,
     },
     /// Try to construct counterexamples for each ingraph in a file
     IngraphSeek {
@@ -254,15 +480,132 @@ enum C {
         /// Bail out after this many checks
         #[arg(long)]
         bailout: Option<usize>,
+        /// Look for this graph in complements instead
+        #[arg(long, value_parser = families::parse_bits)]
+        co: Option<families::GraphArg>,
+        /// Look for induced subgraphs on this many vertices, the graph's first
+        #[arg(long, value_name = "VERTICES")]
+        induced: Option<usize>,
+        /// Only count graphs in this class, which must be closed under
+        /// removing edges: triangle-free, bipartite or planar
+        #[arg(long)]
+        class: Option<class::Class>,
+        /// Digraphs in tournaments rather than graphs in graphs
+        #[arg(long, conflicts_with_all = ["co", "induced", "class"])]
+        directed: bool,
+    },
+    /// Check families of graphs, one per line, for universality
+    IngraphFamily {
+        /// Number of vertices
+        size: usize,
+        /// Families file, graphs separated by spaces
+        path: String,
+        /// Check against all graphs in this file rather than seeking
+        #[arg(long)]
+        all: Option<String>,
+        /// Bail out after this many checks
+        #[arg(long)]
+        bailout: Option<usize>,
+        /// Only count graphs in this class, such as planar or degrees(2,2,2,2);
+        /// without --all it must be closed under removing edges
+        #[arg(long)]
+        class: Option<class::Class>,
     },
     /// Check if a single graph is an ingraph
     IngraphCheck {
         /// Number of vertices
         size: usize,
-        /// Graph
-        bits: BitNum,
-        /// Graphs file
+        /// Graph, or with --directed a digraph's bits; families are graphs
+        bits: String,
+        /// Graphs file, or - for standard input
         path: String,
+        /// Write where the graph was found in each one to this file
+        #[arg(long, conflicts_with = "induced")]
+        certificate: Option<String>,
+        /// Look for this graph in complements instead
+        #[arg(long, value_parser = families::parse_bits)]
+        co: Option<families::GraphArg>,
+        /// Look for induced subgraphs on this many vertices, the graph's first
+        #[arg(long, value_name = "VERTICES")]
+        induced: Option<usize>,
+        /// Only count graphs in this class, such as planar or degrees(2,2,2,2)
+        #[arg(long)]
+        class: Option<class::Class>,
+        /// A digraph in a file of tournaments rather than graphs in graphs
+        #[arg(long, conflicts_with_all = ["certificate", "co", "induced", "class"])]
+        directed: bool,
+    },
+    /// Check if a graph is in one colour of every colouring of the edges
+    ColourCheck {
+        /// Number of vertices
+        size: usize,
+        /// Graph
+        #[arg(value_parser = families::parse_bits)]
+        bits: families::GraphArg,
+        /// Number of colours
+        #[arg(long, default_value_t = 3, value_parser = clap::value_parser!(u8).range(2..))]
+        colours: u8,
+        /// Seek a counterexample instead of checking every colouring
+        #[arg(long)]
+        seek: bool,
+        /// Bail out after this many checks when seeking
+        #[arg(long)]
+        bailout: Option<usize>,
+    },
+    /// Minimal graphs that are not universal ingraphs, with counterexamples
+    MinimalNon {
+        /// Number of vertices
+        size: usize,
+    },
+    /// All universal ingraphs for n, a level at a time, and the maximal ones
+    Poset {
+        /// Number of vertices
+        size: usize,
+        /// Directory for the levels, picked up from if already there [default: output/poset<SIZE>]
+        #[arg(long)]
+        dir: Option<String>,
+        /// Write the Hasse diagram to this file in DOT
+        #[arg(long)]
+        dot: Option<String>,
+    },
+    /// Least n for which a graph is a universal ingraph
+    Threshold {
+        /// Number of vertices
+        size: usize,
+        /// Graph
+        #[arg(value_parser = families::parse_bits)]
+        bits: families::GraphArg,
+        /// Largest n tried
+        #[arg(long, default_value_t = 10)]
+        max: usize,
+        /// Give up each seek after this many graphs
+        #[arg(long, default_value_t = 100_000)]
+        bailout: usize,
+    },
+    /// Ramsey number R(G, F): least n where each graph has G or its complement F
+    Ramsey {
+        /// Number of vertices of G
+        size: usize,
+        /// G
+        #[arg(value_parser = families::parse_bits)]
+        bits: families::GraphArg,
+        /// F
+        #[arg(value_parser = families::parse_bits)]
+        co: families::GraphArg,
+        /// Number of vertices of F, if not the same as G
+        #[arg(long)]
+        co_size: Option<usize>,
+        /// Largest n tried
+        #[arg(long, default_value_t = 10)]
+        max: usize,
+    },
+    /// Check a certificate from ingraph-check
+    Verify {
+        /// Certificate file
+        certificate: String,
+        /// File of all graphs, to check it is complete and covered
+        #[arg(long)]
+        all: Option<String>,
     },
     /// Filter a file of graphs to those within an edge count range
     Filter {
@@ -278,7 +621,17 @@ enum C {
         /// Number of vertices
         size: usize,
         /// Graph
-        bits: BitNum,
+        #[arg(value_parser = families::parse_bits)]
+        bits: families::GraphArg,
+    },
+    /// List the known dense universal ingraphs
+    Catalog {
+        /// Recheck each entry against all graphs
+        #[arg(long)]
+        verify: bool,
+        /// Largest n to recheck
+        #[arg(long, default_value_t = 9)]
+        max: usize,
     },
     /// One-edge extensions whose retracts are all in a file
     Successors {
@@ -298,12 +651,18 @@ enum C {
         #[arg(help = "/ [GRAPHS] ...")]
         graphs: Vec<String>,
     },
+    /// Build a graph from an expression like join(cycle(5), complete(1))
+    Op {
+        /// Expression
+        expr: String,
+    },
     /// Complement of a graph
     Complement {
         /// Number of vertices
         size: usize,
         /// Graph
-        bits: BitNum,
+        #[arg(value_parser = families::parse_bits)]
+        bits: families::GraphArg,
     },
     /// Placeholder for custom operations
     Run {
@@ -312,12 +671,72 @@ enum C {
     },
 }
 
+#[derive(Debug,Clone,Copy,clap::ValueEnum)]
+enum Model {
+    /// Uniform over isomorphism classes
+    Unlabelled,
+    /// Uniform over labelled graphs
+    Labelled,
+    /// Each edge independently with probability p
+    Gnp,
+    /// Uniform over labelled graphs with a given number of edges
+    Gnm,
+    /// Random regular graph
+    Regular,
+    /// Random circulant graph
+    Circulant,
+    /// Random Cayley graph of a random group
+    Cayley,
+}
+
+// Draws graphs from a model with its parameters, exiting if it has none
+struct Sampler {
+    model: Model,
+    size: usize,
+    edges: Option<usize>,
+    p: f64,
+    degree: Option<usize>,
+    dist: Option<random::Unlabelled>,
+}
+
+impl Sampler {
+    fn new(model: Model, size: usize, edges: Option<usize>, p: f64, degree: Option<usize>) -> Self {
+        let dist = match model {
+            Model::Unlabelled => Some(random::Unlabelled::new(size, edges).unwrap_or_else(no_graphs)),
+            _ => None,
+        };
+        Sampler { model, size, edges, p, degree, dist }
+    }
+    fn sample(&self, rng: &mut impl rand::Rng) -> Graph {
+        use rand_distr::Distribution;
+        let size = self.size;
+        match self.model {
+            Model::Unlabelled => self.dist.as_ref().unwrap().sample(rng),
+            Model::Labelled => base::random_graph(rng, size),
+            Model::Gnp => random::gnp(rng, size, self.p),
+            Model::Gnm => random::gnm(rng, size, self.edges.unwrap_or(Graph::triangle(size) / 2))
+                .unwrap_or_else(no_graphs),
+            Model::Regular => random::regular(rng, size, self.degree.unwrap_or(size / 2))
+                .unwrap_or_else(no_graphs),
+            Model::Circulant => random::circulant(rng, size),
+            Model::Cayley => random::cayley(rng, size),
+        }
+    }
+}
+
 #[derive(Debug,Parser)]
 struct Cli {
     #[command(subcommand)]
     command: C,
 }
 
+fn info(gr: &Graph) -> String {
+    let bits = gr.bits();
+    format!("{} {} ({}) {} syms:{} degree_row:{:?}",
+        bits, gr, bits.count_ones(), bits.show_bits(), tools::count_symmetries(gr),
+        tools::build_sorted_row(gr))
+}
+
 fn show_iter(it: impl Iterator<Item=base::BitNum>) {
     for (i, x) in it.enumerate() {
         print!("{}{}", if i == 0 { "" } else { " " }, x);
@@ -325,6 +744,55 @@ fn show_iter(it: impl Iterator<Item=base::BitNum>) {
     println!();
 }
 
+fn no_graphs<T>() -> T {
+    clap::Error::raw(clap::error::ErrorKind::InvalidValue, "No such graphs\n").exit()
+}
+
+// A graph argument for graphs on size vertices, exiting if it does not fit
+fn graph_arg(arg: families::GraphArg, size: usize) -> BitNum {
+    arg.on(size).unwrap_or_else(|e| clap::Error::raw(clap::error::ErrorKind::InvalidValue, e + "\n").exit())
+}
+
+// A digraph size, exiting if it is more than digraph::MAX_SIZE
+fn digraph_size(size: usize) -> usize {
+    if size > digraph::MAX_SIZE {
+        clap::Error::raw(clap::error::ErrorKind::InvalidValue,
+            format!("{} vertices is more than {}\n", size, digraph::MAX_SIZE)).exit()
+    }
+    size
+}
+
+// The families in a file, exiting at the first that cannot be read
+fn read_families(size: usize, path: &str) -> impl Iterator<Item=tools::Target> {
+    fn exit<T>(e: String) -> T { clap::Error::raw(clap::error::ErrorKind::InvalidValue, e + "\n").exit() }
+    tools::read_families(size, path).unwrap_or_else(exit).map(|target| target.unwrap_or_else(exit))
+}
+
+// An --induced vertex count for graphs on size vertices, exiting if it is
+// more than size or leaves out an edge of one of the graphs
+fn induced_arg(induced: Option<usize>, size: usize, grs: &[&Graph]) -> Option<usize> {
+    let vertices = induced?;
+    if vertices > size {
+        clap::Error::raw(clap::error::ErrorKind::InvalidValue,
+            format!("--induced {} is more than {} vertices\n", vertices, size)).exit()
+    }
+    if let Some(gr) = grs.iter().find(|gr| gr.bits() != 0 && tools::infer_size(gr.bits()) > vertices) {
+        clap::Error::raw(clap::error::ErrorKind::InvalidValue,
+            format!("{} has edges beyond the first {} vertices\n", gr.bits(), vertices)).exit()
+    }
+    Some(vertices)
+}
+
+fn parse_probability(s: &str) -> Result<f64, String> {
+    let p: f64 = s.parse().map_err(|e| format!("{}", e))?;
+    if (0.0..=1.0).contains(&p) { Ok(p) } else { Err(format!("{} is not between 0 and 1", p)) }
+}
+
+fn edge_range(min: Option<usize>, max: Option<usize>) -> Option<(usize, usize)> {
+    if min.is_none() && max.is_none() { return None }
+    Some((min.unwrap_or(0), max.unwrap_or(BitNum::BITS as usize)))
+}
+
 fn parse_subgraph_args(strs: Vec<String>) -> Option<(Vec<BitNum>, Vec<BitNum>)> {
     let mut subs = Vec::new();
     let mut sups = Vec::new();
@@ -334,7 +802,7 @@ fn parse_subgraph_args(strs: Vec<String>) -> Option<(Vec<BitNum>, Vec<BitNum>)>
             target = &mut sups;
             continue;
         }
-        target.push(s.parse().ok()?);
+        target.push(families::parse_bits(&s).ok()?.bits);
     }
     if subs.is_empty() || sups.is_empty() {
         None
@@ -346,56 +814,190 @@ fn parse_subgraph_args(strs: Vec<String>) -> Option<(Vec<BitNum>, Vec<BitNum>)>
 pub fn main() {
     let args = Cli::parse();
     match args.command {
-        C::Enumerate { size } => {
+        C::Enumerate { size, directed: false } => {
             enumerate(size, None);
         }
-        C::EnumerateFilter { size, min, max } => {
-            enumerate(size, Some((min, max)));
+        C::Enumerate { size, directed: true } => {
+            for bits in digraph::all_tournaments(digraph_size(size)) {
+                println!("{}", bits);
+            }
+        }
+        C::EnumerateFilter { size, min, max, skip, take } => {
+            enumerate::enumerate_window(size, Some((min, max)), skip, take.unwrap_or(u64::MAX),
+                |bits| println!("{}", bits));
+        }
+        C::Rank { size, bits, min, max } => {
+            let gr = Graph::from_bits(size, graph_arg(bits, size));
+            println!("{:?}", enumerate::rank(&gr, edge_range(min, max)));
+        }
+        C::Unrank { size, index, min, max } => {
+            println!("{:?}", enumerate::unrank(size, edge_range(min, max), index));
         }
         C::EnumerateMiddle { size } => {
             let tri = Graph::triangle(size);
             let half = tri / 2;
-            if tri % 2 == 0 {
+            if tri.is_multiple_of(2) {
                 enumerate_middle(size);
             } else {
                 enumerate(size, Some((half, half)));
             }
         }
+        C::SelfComplementary { size } => {
+            for bits in selfcomp::self_complementary(size) {
+                println!("{}", bits);
+            }
+        }
+        C::CrossCheck { max } => {
+            for size in 1..=max {
+                let (orderly, augmented) = augment::cross_check(size);
+                let a: BTreeSet<_> = orderly.iter().collect();
+                let b: BTreeSet<_> = augmented.iter().collect();
+                // Both against the counts by edges from Burnside's lemma
+                let expected = tools::graph_counts(size);
+                let counted = |grs: &[BitNum]| Some(augment::counts_by_edges(size, grs)) == expected;
+                let ok = orderly == augmented && counted(&orderly) && counted(&augmented);
+                println!("{} {} {} {:?} {}", size, orderly.len(), augmented.len(),
+                    expected.map(|counts| counts.iter().sum::<u128>()), if ok { "ok" } else { "MISMATCH" });
+                if !ok {
+                    print!(" orderly only:");
+                    show_iter(a.difference(&b).take(10).map(|x| **x));
+                    print!(" augmented only:");
+                    show_iter(b.difference(&a).take(10).map(|x| **x));
+                }
+            }
+        }
+        C::Random { size, count, seed, model, edges, p, degree } => {
+            use rand::SeedableRng;
+            let rng = &mut match seed {
+                Some(seed) => rand::rngs::StdRng::seed_from_u64(seed),
+                None => rand::rngs::StdRng::from_entropy(),
+            };
+            let sampler = Sampler::new(model, size, edges, p, degree);
+            for _ in 0..count {
+                println!("{}", enumerate::to_best(&sampler.sample(rng)).bits());
+            }
+        }
+        C::Estimate { size, bits, co, samples, seed, model, edges, p, degree, show } => {
+            let (bits, co) = (graph_arg(bits, size), co.map(|co| graph_arg(co, size)));
+            let seed = seed.unwrap_or_else(|| {
+                let seed = rand::random();
+                eprintln!("Seed: {}", seed);
+                seed
+            });
+            let gr = Graph::from_bits(size, bits);
+            let target = tools::Target::pair(&gr, &Graph::from_bits(size, co.unwrap_or(bits)));
+            let sampler = Sampler::new(model, size, edges, p, degree);
+            let est = estimate::estimate(&target, samples, seed, show, |rng| sampler.sample(rng));
+            let (lo, hi) = est.wilson(1.96);
+            println!("{},{},{},{:.6},{:.6},{:.6}", bits, est.samples, est.failures, est.rate(), lo, hi);
+            for ce in est.counterexamples {
+                println!("{},{}", ce.bits(), ce);
+            }
+        }
         C::Run { size } => {
             run_graphs(size);
         }
-        C::Retract { bits } => {
-            let gr = tools::infer_graph(bits);
-            let seen = tools::bump(&gr, false);
+        C::Retract { bits, vertex, depth } => {
+            let gr = bits.graph();
+            let seen = match (vertex, depth) {
+                (true, _) => tools::bump_vertex(&gr, false),
+                (_, 1) => tools::bump(&gr, false),
+                _ => tools::bump_depth(&gr, 0, depth),
+            };
             show_iter(seen.into_iter());
         }
-        C::Extend { size, bits } => {
+        C::Extend { size, bits, vertex, depth } => {
+            let bits = graph_arg(bits, size);
             let gr = Graph::from_bits(size, bits);
-            let seen = tools::bump(&gr, true);
+            let seen = match (vertex, depth) {
+                (true, _) => tools::bump_vertex(&gr, true),
+                (_, 1) => tools::bump(&gr, true),
+                _ => tools::bump_depth(&gr, depth, 0),
+            };
             show_iter(seen.into_iter());
         }
         C::Stats { path } => {
             stats(path);
         }
-        C::Misses { size, bits } => {
-            for bits in bits {
+        C::Misses { size, bits, list: true } => {
+            let all = all_graphs(size);
+            for bits in bits.into_iter().map(|arg| graph_arg(arg, size)) {
+                let gr = Graph::from_bits(size, bits);
+                let groups = tools::misses_by_edges(all.iter().cloned(), &gr);
+                println!("{},{},{}", gr.bits(), groups.values().map(Vec::len).sum::<usize>(), gr);
+                for (edges, grs) in groups {
+                    println!("{} edges: {}", edges, grs.len());
+                    for h in grs {
+                        println!("  {},{},{},{},{}", h.bits(), edges, tools::count_symmetries(&h),
+                            selfcomp::is_self_complementary(&h).is_some(), h);
+                    }
+                }
+            }
+        }
+        C::Misses { size, bits, list: false } => {
+            for bits in bits.into_iter().map(|arg| graph_arg(arg, size)) {
                 let gr = Graph::from_bits(size, bits);
                 let counts = miss_counts(&gr);
                 println!("{},{},{},{},{}", gr.bits(), counts.0, counts.1, counts.2, gr);
             }
         }
-        C::IngraphScan { size, path } => {
+        C::IngraphScan { size, path, directed: true, .. } => {
+            let size = digraph_size(size);
+            directed_scan(size, tools::read_graphs(size, &path));
+        }
+        C::IngraphScan { size, path, co, induced, class, directed: false } => {
             let pool = tools::read_graphs(size, &path);
-            ingraph_scan(size, pool);
+            ingraph_scan(size, pool, co.map(|co| graph_arg(co, size)), induced, class);
+        }
+        C::IngraphSeek { size, path, bailout, directed: true, .. } => {
+            directed_seek(tools::read_graphs(digraph_size(size), &path), bailout.unwrap_or(usize::MAX));
         }
-        C::IngraphSeek { size, path, bailout } => {
+        C::IngraphSeek { size, path, bailout, co, induced, class, directed: false } => {
+            let co = co.map(|co| graph_arg(co, size));
             eprintln!("Threads: {}", rayon::current_num_threads());
-            let pool = tools::read_graphs(size, &path);
-            ingraph_seek(pool, bailout.unwrap_or(usize::MAX));
+            let pool = tools::read_graphs(size, &path).map(|gr: Graph| {
+                let co = co.map_or_else(|| gr.clone(), |co| Graph::from_bits(size, co));
+                tools::Target::pair(&gr, &co).with_induced(induced_arg(induced, size, &[&gr, &co]))
+            });
+            ingraph_seek(pool, bailout.unwrap_or(usize::MAX), class.as_ref());
         }
-        C::IngraphCheck { size, bits, path } => {
+        C::IngraphFamily { size, path, all: None, bailout, class } => {
+            ingraph_seek(read_families(size, &path), bailout.unwrap_or(usize::MAX), class.as_ref());
+        }
+        C::IngraphFamily { size, path, all: Some(all), class, .. } => {
+            let all: Vec<BitNum> = tools::read_graphs(size, &all).collect();
+            for target in read_families(size, &path) {
+                let label = target_label(&target);
+                let counter = noncover(all.iter().cloned(), target, class.as_ref());
+                println!("{},{:?}", label, counter);
+            }
+        }
+        C::IngraphCheck { size, bits, path, directed: true, .. } => {
+            let size = digraph_size(size);
+            let bits: BitNum = bits.trim().parse().unwrap_or_else(|_|
+                clap::Error::raw(clap::error::ErrorKind::InvalidValue, "A digraph is given by its bits, not a family\n").exit()
+            );
+            let sub = digraph::Digraph::from_bits(size, bits);
+            let ans = tools::read_graphs(size, &path).find(|sup| !digraph::is_subdigraph_of(&sub, sup));
+            println!("{:?} {} {:?} {:?}",
+                bits,
+                sub,
+                ans.clone().map(|x: digraph::Digraph| x.bits()),
+                ans.map(|x| format!("{}", x))
+            );
+        }
+        C::IngraphCheck { size, bits, path, certificate, co, induced, class, directed: false } => {
+            let bits = graph_arg(families::parse_bits(&bits).unwrap_or_else(|e|
+                clap::Error::raw(clap::error::ErrorKind::InvalidValue, e + "\n").exit()
+            ), size);
+            let co = co.map(|co| graph_arg(co, size));
             let gr = Graph::from_bits(size, bits);
-            let ans = ingraph_check(&gr, tools::read_graphs(size, &path));
+            let co = Graph::from_bits(size, co.unwrap_or(bits));
+            let mut cert = certificate.map(|path| cert::Writer::create(&path, &gr, &co));
+            let target = tools::Target::pair(&gr, &co).with_induced(induced_arg(induced, size, &[&gr, &co]));
+            let list = tools::read_graphs(size, &path)
+                .filter(|sup: &Graph| class.as_ref().is_none_or(|class| class.contains(sup)));
+            let ans = ingraph_check(&target, list, cert.as_mut());
             println!("{:?} {} {:?} {:?}",
                 bits,
                 gr,
@@ -403,6 +1005,73 @@ pub fn main() {
                 ans.map(|x| format!("{}", x))
             );
         }
+        C::ColourCheck { size, bits, colours, seek, bailout } => {
+            let bits = graph_arg(bits, size);
+            let gr = Graph::from_bits(size, bits);
+            let colours = colours as usize;
+            let ans = if seek {
+                colour::seek(&gr, colours, bailout.unwrap_or(usize::MAX)).0
+            } else {
+                colour::noncovers(colour::all_colourings(size, colours).iter(), &gr).next().cloned()
+            };
+            println!("{},{},{:?}", bits, colours, ans.map(|col| col.to_string()));
+        }
+        C::MinimalNon { size } => {
+            poset::walk(size, &all_graphs(size), |level| {
+                eprintln!("Level {}: {} ingraphs, {} minimal non-ingraphs",
+                    level.edges, level.ingraphs.len(), level.minimal_non.len());
+                for &(bits, ce) in &level.minimal_non {
+                    println!("{},{},{},{}", bits, Graph::from_bits(size, bits), level.edges, ce);
+                }
+            });
+        }
+        C::Poset { size, dir, dot } => {
+            let dir = std::path::PathBuf::from(dir.unwrap_or_else(|| format!("output/poset{}", size)));
+            let stored = poset::load_levels(&dir, size).unwrap_or_else(|e|
+                clap::Error::raw(clap::error::ErrorKind::InvalidValue, e + "\n").exit());
+            eprintln!("Stored levels: {}", stored.len());
+            let show = |level: &poset::Level|
+                println!("{},{},{}", level.edges, level.ingraphs.len(), level.minimal_non.len());
+            stored.iter().for_each(show);
+            let levels = poset::extend(size, &all_graphs(size), stored, |level| {
+                poset::store_level(&dir, size, level).unwrap();
+                show(level);
+            });
+            for bits in poset::maximal(size, &levels) {
+                let gr = Graph::from_bits(size, bits);
+                println!("{},{},{}", bits, gr, gr.edge_count());
+            }
+            if let Some(path) = dot {
+                std::fs::write(path, poset::hasse_dot(size, &levels)).unwrap();
+            }
+        }
+        C::Threshold { size, bits, max, bailout } => {
+            let bits = graph_arg(bits, size);
+            let gr = Graph::from_bits(size, bits);
+            match threshold::threshold(&gr, max, bailout, all_graphs) {
+                Some(t) => println!("{},{},{:?}", bits, t.n, t.counterexample.map(|ce| ce.bits())),
+                None => println!("{},none up to {}", bits, max),
+            }
+        }
+        C::Ramsey { size, bits, co, co_size, max } => {
+            let bits = graph_arg(bits, size);
+            let co_size = co_size.unwrap_or(size);
+            let sub = Graph::from_bits(size, bits);
+            let co = Graph::from_bits(co_size, graph_arg(co, co_size));
+            match ramsey::ramsey(&sub, &co, max) {
+                Some(r) => println!("{},{},{},{:?}", bits, co.bits(), r.n, r.critical.map(|gr| gr.bits())),
+                None => println!("{},{},none up to {}", bits, co.bits(), max),
+            }
+        }
+        C::Verify { certificate, all } => {
+            match cert::verify(&certificate, all.as_deref()) {
+                Ok(summary) => println!("ok: {}", summary),
+                Err(err) => {
+                    println!("FAILED: {}", err);
+                    std::process::exit(1);
+                }
+            }
+        }
         C::Filter { min, max, path } => {
             for g in tools::read_graphs::<BitNum>(max, &path) {
                 let ct = g.count_ones() as usize;
@@ -412,10 +1081,29 @@ pub fn main() {
             }
         }
         C::Info { size, bits } => {
-            let gr = Graph::from_bits(size, bits);
-            println!("{} {} ({}) {} syms:{} degree_row:{:?}",
-                bits, gr, bits.count_ones(), bits.show_bits(), tools::count_symmetries(&gr),
-                tools::build_sorted_row(&gr));
+            println!("{}", info(&Graph::from_bits(size, graph_arg(bits, size))));
+        }
+        C::Catalog { verify, max } => {
+            for entry in catalog::CATALOG {
+                let status = entry.status.map_or("no claim".to_string(), |status| status.to_string());
+                println!("{} {} [{}] {} vertices:{}",
+                    entry.n, entry.name, status, info(&entry.graph()), entry.vertices());
+            }
+            if !verify { return }
+            let mut failed = false;
+            for n in 1..=max {
+                let entries: Vec<_> = catalog::CATALOG.iter().filter(|e| e.n == n).collect();
+                if entries.is_empty() { continue }
+                let all = enumerate::all_graphs(n);
+                for entry in entries {
+                    match catalog::verify(entry, &all) {
+                        Ok(()) if entry.status.is_none() => println!("{} {}: no claim", n, entry.bits),
+                        Ok(()) => println!("{} {}: ok", n, entry.bits),
+                        Err(err) => { println!("{} {}: FAILED: {}", n, entry.bits, err); failed = true }
+                    }
+                }
+            }
+            if failed { std::process::exit(1) }
         }
         C::Successors { size, path, max } => {
             let pool = tools::read_graphs(size, &path);
@@ -451,8 +1139,15 @@ pub fn main() {
             }
             if !table { println!() }
         }
+        C::Op { expr } => {
+            let gr = families::parse(&expr).unwrap_or_else(|e|
+                clap::Error::raw(clap::error::ErrorKind::InvalidValue, e + "\n").exit()
+            );
+            println!("{} {} {} ({}) best:{}",
+                gr.bits(), gr.size, gr, gr.edge_count(), enumerate::to_best(&gr).bits());
+        }
         C::Complement { size, bits } => {
-            let gr = enumerate::to_best(&Graph::from_bits(size, bits).complement());
+            let gr = enumerate::to_best(&Graph::from_bits(size, graph_arg(bits, size)).complement());
             println!("{}", gr.bits());
         }
     }
@@ -32,6 +32,18 @@ impl Perm {
         use super::testers::*;
         PermDistr(size).sample(rng)
     }
+    // Cycles of the given lengths on consecutive points, the rest fixed
+    pub fn from_cycle_type(size: usize, cycles: &[usize]) -> Self {
+        let mut vec: Vec<usize> = (0..size).collect();
+        let mut start = 0;
+        for &len in cycles {
+            for i in 0..len {
+                vec[start + i] = start + (i + 1) % len;
+            }
+            start += len;
+        }
+        Perm::new(vec)
+    }
     pub fn is_valid(&self) -> bool {
         let mut set = BTreeSet::new();
         for it in &self.vec {
@@ -60,6 +72,144 @@ pub fn all_perms<'a>(size: usize) -> impl Iterator<Item=Perm> + 'a {
     (0..size).permutations(size).map(Perm::new_unsafe)
 }
 
+// Ranks of the keys, equal keys sharing one
+fn ranks<K: Ord>(keys: &[K]) -> Vec<usize> {
+    let sorted: Vec<&K> = keys.iter().collect::<BTreeSet<_>>().into_iter().collect();
+    keys.iter().map(|k| sorted.binary_search(&k).unwrap()).collect()
+}
+
+// Classes split by the relations to each class until stable, keeping the
+// order of the classes split
+fn refine(mut cls: Vec<usize>, rel: &impl Fn(usize, usize) -> usize) -> Vec<usize> {
+    let n = cls.len();
+    loop {
+        let keys: Vec<_> = (0..n).map(|v| {
+            let mut nbrs: Vec<_> = (0..n).filter(|&u| u != v).map(|u| (rel(v, u), rel(u, v), cls[u])).collect();
+            nbrs.sort();
+            (cls[v], nbrs)
+        }).collect();
+        let next = ranks(&keys);
+        let count = |c: &[usize]| c.iter().collect::<BTreeSet<_>>().len();
+        if count(&next) == count(&cls) { return next }
+        cls = next;
+    }
+}
+
+// Search over the orders of the points by increasing invariant, `rel` being
+// the relation between distinct points.  Ties are broken a point at a time,
+// refining after each; a point is not tried if an automorphism found so far
+// fixes the points chosen and maps a tried one to it, and an order labelled
+// as the first one found is an automorphism, after which the rest of its
+// branch is an image of the first's.
+struct TieSearch<R, L, T> {
+    rel: R,
+    label: L,
+    // Points chosen, order and label of the first order found
+    first: Option<(Vec<usize>, Vec<usize>, T)>,
+    best: Option<T>,
+    gens: Vec<Perm>,
+}
+
+impl<R: Fn(usize, usize) -> usize, L: Fn(&[usize]) -> T, T: Ord> TieSearch<R, L, T> {
+    fn twins(&self, a: usize, b: usize, n: usize) -> bool {
+        let rel = &self.rel;
+        rel(a, b) == rel(b, a)
+            && (0..n).filter(|&u| u != a && u != b).all(|u| rel(a, u) == rel(b, u) && rel(u, a) == rel(u, b))
+    }
+    fn same_orbit(&self, fixed: &[usize], a: usize, b: usize) -> bool {
+        let gens: Vec<&Perm> = self.gens.iter().filter(|p| fixed.iter().all(|&v| p.apply(v) == v)).collect();
+        let mut orbit = vec![a];
+        let mut i = 0;
+        while i < orbit.len() {
+            for p in &gens {
+                let w = p.apply(orbit[i]);
+                if !orbit.contains(&w) { orbit.push(w) }
+            }
+            i += 1;
+        }
+        orbit.contains(&b)
+    }
+    // How many points chosen to go back up to, if not just to the parent
+    fn go(&mut self, cls: Vec<usize>, path: &mut Vec<usize>) -> Option<usize> {
+        let cls = refine(cls, &self.rel);
+        let n = cls.len();
+        let mut sizes = vec![0; n];
+        for &c in &cls { sizes[c] += 1 }
+        let Some(c) = (0..n).filter(|&c| sizes[c] > 1).min() else {
+            let order: Vec<usize> = (0..n).sorted_by_key(|&v| cls[v]).collect();
+            let label = (self.label)(&order);
+            let Some((first_path, first_order, first_label)) = &self.first else {
+                self.first = Some((path.clone(), order, label));
+                return None;
+            };
+            if label == *first_label {
+                let mut image = vec![0; n];
+                for (&a, &b) in first_order.iter().zip(&order) { image[a] = b }
+                self.gens.push(Perm::new_unsafe(image));
+                return Some(first_path.iter().zip(path.iter()).take_while(|(a, b)| a == b).count());
+            }
+            if self.best.as_ref().is_none_or(|b| label < *b) { self.best = Some(label) }
+            return None;
+        };
+        let mut tried: Vec<usize> = Vec::new();
+        for v in (0..n).filter(|&v| cls[v] == c) {
+            if let Some(&t) = tried.iter().find(|&&t| self.twins(t, v, n)) {
+                self.gens.push(Perm::from_fn(n, |u| if u == t { v } else if u == v { t } else { u }));
+                continue;
+            }
+            if tried.iter().any(|&t| self.same_orbit(path, t, v)) { continue }
+            tried.push(v);
+            path.push(v);
+            let back = self.go((0..n).map(|u| 2 * cls[u] + (u != v) as usize).collect(), path);
+            path.pop();
+            if let Some(depth) = back { if depth < path.len() { return back } }
+        }
+        None
+    }
+}
+
+// Least label and generators of the automorphism group
+fn tie_search<I: Ord, T: Ord>(inv: &[I], rel: impl Fn(usize, usize) -> usize, label: impl Fn(&[usize]) -> T) -> (T, Vec<Perm>) {
+    let mut search = TieSearch { rel, label, first: None, best: None, gens: Vec::new() };
+    search.go(ranks(inv), &mut Vec::new());
+    let (_, _, first) = search.first.unwrap();
+    let best = match search.best { Some(b) if b < first => b, _ => first };
+    (best, search.gens)
+}
+
+/// Least `label(order)` over orders of the points by increasing `inv`, ties
+/// broken as any isomorphism would, `rel` being the relation between
+/// distinct points.
+pub fn canonical_order<I: Ord, T: Ord>(inv: &[I], rel: impl Fn(usize, usize) -> usize, label: impl Fn(&[usize]) -> T) -> T {
+    tie_search(inv, rel, label).0
+}
+
+/// Generators of the automorphism group of `rel`, found by the same search.
+pub fn automorphisms<I: Ord, T: Ord>(inv: &[I], rel: impl Fn(usize, usize) -> usize, label: impl Fn(&[usize]) -> T) -> Vec<Perm> {
+    tie_search(inv, rel, label).1
+}
+
+// Partitions of n into non-increasing parts, i.e. the cycle types of S_n
+pub fn partitions(n: usize) -> Vec<Vec<usize>> {
+    fn go(n: usize, max: usize) -> Vec<Vec<usize>> {
+        if n == 0 { return vec![Vec::new()] }
+        (1..=max.min(n)).rev().flat_map(|part| {
+            go(n - part, part).into_iter().map(move |mut rest| { rest.insert(0, part); rest })
+        }).collect()
+    }
+    go(n, n)
+}
+
+// Number of permutations with the given cycle type
+pub fn class_size(cycles: &[usize]) -> u128 {
+    let n: usize = cycles.iter().sum();
+    let mut size: u128 = (1..=n as u128).product();
+    for (mult, len) in cycles.iter().dedup_with_count() {
+        size /= (*len as u128).pow(mult as u32) * (1..=mult as u128).product::<u128>();
+    }
+    size
+}
+
 impl_op_ex!(* |a: &Perm, b: &Perm| -> Perm {
     assert_eq!(a.size(), b.size(), "Composed perms must have same size.");
     Perm::new_unsafe((0..a.size()).map(|i| a.vec[b.vec[i]]).collect())
@@ -0,0 +1,231 @@
+/*!
+    Being a universal ingraph for n is closed under taking subgraphs, so the
+    ingraphs are determined by the minimal graphs that are not: those whose
+    one-edge retracts all are.
+
+    Both are found a level, that is an edge count, at a time, as in Apriori:
+    the candidates at a level are the one-edge extensions of the ingraphs
+    below whose retracts are all ingraphs.  Levels can be stored, one file
+    each with the size in another, and a walk picks up after the last one
+    stored for the same size.
+*/
+
+use crate::base::{BitNum,Graph,Bits};
+use crate::tools;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+use std::path::Path;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Level {
+    pub edges: usize,
+    pub ingraphs: Vec<BitNum>,
+    // Each with a graph it is not in, nor in its complement
+    pub minimal_non: Vec<(BitNum, BitNum)>,
+}
+
+/// Walks the levels for graphs on `size` vertices, `all` being every graph
+/// on them, until one has no ingraphs; `each` sees each level as it is done.
+pub fn walk(size: usize, all: &[BitNum], each: impl FnMut(&Level)) -> Vec<Level> {
+    extend(size, all, Vec::new(), each)
+}
+
+/// As `walk`, after the levels already done from 0 up.
+pub fn extend(size: usize, all: &[BitNum], mut levels: Vec<Level>, mut each: impl FnMut(&Level)) -> Vec<Level> {
+    // Counterexamples found so far, tried before all graphs
+    let mut counterexamples: Vec<BitNum> = levels.iter()
+        .flat_map(|level| level.minimal_non.iter().map(|&(_, ce)| ce))
+        .collect::<BTreeSet<_>>().into_iter().collect();
+    let mut below: BTreeSet<BitNum> = match levels.last() {
+        Some(level) if level.ingraphs.is_empty() => return levels,
+        Some(level) => level.ingraphs.iter().cloned().collect(),
+        None => BTreeSet::new(),
+    };
+    for edges in levels.len()..=Graph::triangle(size) {
+        let candidates: BTreeSet<BitNum> = if edges == 0 {
+            BTreeSet::from([0])
+        } else {
+            below.iter().flat_map(|&gr| tools::bump(&Graph::from_bits(size, gr), true)).collect()
+        };
+        let mut level = Level { edges, ingraphs: Vec::new(), minimal_non: Vec::new() };
+        for cand in candidates {
+            let gr = Graph::from_bits(size, cand);
+            if !tools::bump(&gr, false).iter().all(|r| below.contains(r)) { continue }
+            let chkce = tools::noncovers(counterexamples.iter().rev().cloned(), &gr).next();
+            match chkce.or_else(|| tools::noncovers(all.iter().cloned(), &gr).next()) {
+                Some(ce) => {
+                    if !counterexamples.contains(&ce) { counterexamples.push(ce) }
+                    level.minimal_non.push((cand, ce));
+                }
+                None => level.ingraphs.push(cand),
+            }
+        }
+        each(&level);
+        below = level.ingraphs.iter().cloned().collect();
+        levels.push(level);
+        if below.is_empty() { break }
+    }
+    levels
+}
+
+/// Ingraphs with no ingraph among their one-edge extensions.
+pub fn maximal(size: usize, levels: &[Level]) -> Vec<BitNum> {
+    let mut ans = Vec::new();
+    for (e, level) in levels.iter().enumerate() {
+        let above: BTreeSet<BitNum> = levels.get(e + 1).map_or_else(BTreeSet::new, |l| l.ingraphs.iter().cloned().collect());
+        ans.extend(level.ingraphs.iter().cloned()
+            .filter(|&gr| tools::bump(&Graph::from_bits(size, gr), true).is_disjoint(&above)));
+    }
+    ans
+}
+
+/// Hasse diagram of the ingraphs in Graphviz DOT, an arc for each one-edge
+/// extension, maximal elements doubly circled.
+pub fn hasse_dot(size: usize, levels: &[Level]) -> String {
+    let top: BTreeSet<BitNum> = maximal(size, levels).into_iter().collect();
+    let mut dot = format!("digraph ingraphs{} {{\n    rankdir=BT;\n", size);
+    for (e, level) in levels.iter().enumerate() {
+        let above: BTreeSet<BitNum> = levels.get(e + 1).map_or_else(BTreeSet::new, |l| l.ingraphs.iter().cloned().collect());
+        for &gr in &level.ingraphs {
+            let shape = if top.contains(&gr) { ",peripheries=2" } else { "" };
+            writeln!(dot, "    g{} [label=\"{}\\n{}\"{}];", gr, gr, Graph::from_bits(size, gr), shape).unwrap();
+            for ext in tools::bump(&Graph::from_bits(size, gr), true).intersection(&above) {
+                writeln!(dot, "    g{} -> g{};", gr, ext).unwrap();
+            }
+        }
+    }
+    dot.push_str("}\n");
+    dot
+}
+
+// One line per graph checked: the graph, then the counterexample if any
+fn format_level(level: &Level) -> String {
+    let mut out = String::new();
+    for &gr in &level.ingraphs { writeln!(out, "{},None", gr).unwrap() }
+    for &(gr, ce) in &level.minimal_non { writeln!(out, "{},Some({})", gr, ce).unwrap() }
+    out
+}
+
+fn parse_level(edges: usize, text: &str) -> Option<Level> {
+    let mut level = Level { edges, ingraphs: Vec::new(), minimal_non: Vec::new() };
+    for line in text.lines() {
+        let (gr, ce) = line.split_once(',')?;
+        let gr = gr.parse().ok()?;
+        match ce.strip_prefix("Some(").and_then(|ce| ce.strip_suffix(')')) {
+            Some(ce) => level.minimal_non.push((gr, ce.parse().ok()?)),
+            None if ce == "None" => level.ingraphs.push(gr),
+            None => return None,
+        }
+    }
+    Some(level)
+}
+
+fn level_path(dir: &Path, edges: usize) -> std::path::PathBuf { dir.join(format!("level{}.txt", edges)) }
+
+fn size_path(dir: &Path) -> std::path::PathBuf { dir.join("size.txt") }
+
+/// Writes a level for graphs on `size` vertices into `dir` as
+/// level{edges}.txt, whole or not at all, the size going in size.txt.
+pub fn store_level(dir: &Path, size: usize, level: &Level) -> std::io::Result<()> {
+    std::fs::create_dir_all(dir)?;
+    if !size_path(dir).exists() { std::fs::write(size_path(dir), format!("{}\n", size))? }
+    let path = level_path(dir, level.edges);
+    let tmp = path.with_extension("tmp");
+    std::fs::write(&tmp, format_level(level))?;
+    std::fs::rename(tmp, path)
+}
+
+/// Levels for `size` stored in `dir`, from 0 up to the first missing one;
+/// an error if they are for another size.
+pub fn load_levels(dir: &Path, size: usize) -> Result<Vec<Level>, String> {
+    let stored = std::fs::read_to_string(size_path(dir)).ok();
+    if let Some(stored) = &stored {
+        if stored.trim().parse::<usize>() != Ok(size) {
+            return Err(format!("{} holds levels for {} vertices, not {}", dir.display(), stored.trim(), size))
+        }
+    }
+    let mut levels = Vec::new();
+    loop {
+        let path = level_path(dir, levels.len());
+        let Ok(text) = std::fs::read_to_string(&path) else { break };
+        let level = parse_level(levels.len(), &text).ok_or_else(|| format!("bad level file {}", path.display()))?;
+        levels.push(level);
+    }
+    if stored.is_none() && !levels.is_empty() {
+        return Err(format!("{} holds levels with no size.txt", dir.display()))
+    }
+    Ok(levels)
+}
+
+/// The minimal non-ingraphs for `size`, with their counterexamples.
+pub fn minimal_non_ingraphs(size: usize, all: &[BitNum]) -> Vec<(Graph, Graph)> {
+    walk(size, all, |_| ()).into_iter()
+        .flat_map(|level| level.minimal_non)
+        .map(|(gr, ce)| (Graph::from_bits(size, gr), Graph::from_bits(size, ce)))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::enumerate;
+
+    // From universality of every graph
+    fn naive_minimal_non_ingraphs(size: usize, all: &[BitNum]) -> BTreeSet<BitNum> {
+        let universal: BTreeSet<BitNum> = all.iter().cloned()
+            .filter(|&gr| tools::noncovers(all.iter().cloned(), &Graph::from_bits(size, gr)).next().is_none())
+            .collect();
+        all.iter().cloned()
+            .filter(|gr| !universal.contains(gr))
+            .filter(|&gr| tools::bump(&Graph::from_bits(size, gr), false).iter().all(|r| universal.contains(r)))
+            .collect()
+    }
+
+    #[test]
+    fn test_minimal_non() {
+        for size in 2..=6 {
+            let all = enumerate::all_graphs(size);
+            let found = minimal_non_ingraphs(size, &all);
+            let bits: BTreeSet<BitNum> = found.iter().map(|(gr, _)| gr.bits()).collect();
+            assert_eq!(bits, naive_minimal_non_ingraphs(size, &all), "{}", size);
+            assert_eq!(bits.len(), found.len());
+            for (gr, ce) in &found {
+                assert!(!tools::is_subgraph_of(gr, ce) && !tools::is_subgraph_of(gr, &ce.complement()));
+            }
+        }
+        // K3 fails at 5, by C5, while the 5 DUI is the densest ingraph
+        let levels = walk(5, &enumerate::all_graphs(5), |_| ());
+        assert!(levels[3].minimal_non.iter().any(|&(gr, ce)| gr == 7 && ce.count_ones() == 5));
+        assert_eq!(levels[3].ingraphs, vec![13]);
+        assert!(levels[4].ingraphs.is_empty());
+    }
+
+    #[test]
+    fn test_poset() {
+        let all = enumerate::all_graphs(6);
+        let levels = walk(6, &all, |_| ());
+        // K3, as R(3, 3) = 6, and the 6 DUI
+        assert_eq!(maximal(6, &levels), vec![7, 94]);
+        let dot = hasse_dot(6, &levels);
+        assert_eq!(dot.matches("peripheries=2").count(), 2);
+        assert_eq!(dot.matches(" [label=").count(), levels.iter().map(|l| l.ingraphs.len()).sum::<usize>());
+        for level in &levels {
+            assert_eq!(parse_level(level.edges, &format_level(level)).as_ref(), Some(level));
+        }
+        // Picking up part way gives the same levels
+        let mut seen = Vec::new();
+        let rest = extend(6, &all, levels[..3].to_vec(), |level| seen.push(level.edges));
+        assert_eq!(rest, levels);
+        assert_eq!(seen, (3..levels.len()).collect::<Vec<_>>());
+        assert_eq!(extend(6, &all, levels.clone(), |_| panic!()), levels);
+        // Stored levels come back only for the same size
+        let dir = std::env::temp_dir().join(format!("poset-test-{}", std::process::id()));
+        for level in &levels[..3] { store_level(&dir, 6, level).unwrap() }
+        assert_eq!(load_levels(&dir, 6), Ok(levels[..3].to_vec()));
+        assert!(load_levels(&dir, 7).is_err());
+        std::fs::remove_file(size_path(&dir)).unwrap();
+        assert!(load_levels(&dir, 6).is_err());
+        std::fs::remove_dir_all(&dir).unwrap();
+        assert_eq!(load_levels(&dir, 6), Ok(Vec::new()));
+    }
+}
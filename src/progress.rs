@@ -5,6 +5,10 @@ pub struct Progress {
     next: Cell<Instant>,
 }
 
+impl Default for Progress {
+    fn default() -> Self { Self::new() }
+}
+
 impl Progress {
     pub fn new() -> Self {
         Self { next: Instant::now().into() }
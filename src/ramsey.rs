@@ -0,0 +1,79 @@
+/*!
+    Graph Ramsey numbers R(G, F): the least n such that for every graph H on
+    n vertices, G is in H or F is in the complement of H.  R(G, G) is the
+    threshold of G as an ingraph.
+
+    Graphs on n vertices with neither are built up a vertex at a time, since
+    deleting a vertex of one leaves another; R(G, F) is the first n with none.
+*/
+
+use crate::base::{BitNum,Graph,Bits,MAX_SIZE};
+use crate::tools;
+use crate::enumerate;
+use rayon::prelude::*;
+use std::collections::BTreeSet;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Ramsey {
+    pub n: usize,
+    // A graph on n - 1 vertices without G, whose complement is without F
+    pub critical: Option<Graph>,
+}
+
+// Neither sub in gr nor co in its complement; a graph too big to fit counts
+// as absent.
+fn avoids(gr: &Graph, sub: &Graph, co: &Graph) -> bool {
+    let n = gr.size;
+    let sub_in = sub.size <= n && tools::is_subgraph_of(&Graph::from_bits(n, sub.bits()), gr);
+    let co_in = co.size <= n && tools::is_subgraph_of(&Graph::from_bits(n, co.bits()), &gr.complement());
+    !sub_in && !co_in
+}
+
+/// R(sub, co) if it is at most `max`, counting isolated vertices up to each `size`.
+pub fn ramsey(sub: &Graph, co: &Graph, max: usize) -> Option<Ramsey> {
+    let mut level: BTreeSet<BitNum> = BTreeSet::new();
+    if !avoids(&Graph::from_bits(1, 0), sub, co) {
+        return if max >= 1 { Some(Ramsey { n: 1, critical: None }) } else { None }
+    }
+    level.insert(0);
+    for n in 2..=max.min(MAX_SIZE) {
+        let last = Graph::triangle(n - 1);
+        let next: BTreeSet<BitNum> = level.par_iter().flat_map_iter(|&parent| {
+            (0 .. 1 << (n - 1) as BitNum).filter_map(move |row| {
+                let child = Graph::from_bits(n, parent | row << last);
+                if avoids(&child, sub, co) { Some(enumerate::to_best(&child).bits()) } else { None }
+            }).collect::<Vec<_>>()
+        }).collect();
+        if next.is_empty() {
+            let critical = Graph::from_bits(n - 1, *level.first().unwrap());
+            return Some(Ramsey { n, critical: Some(critical) })
+        }
+        level = next;
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::families;
+
+    #[test]
+    fn test_ramsey() {
+        let r = |sub: Graph, co: Graph| ramsey(&sub, &co, 9).map(|r| r.n);
+        let k = families::complete;
+        // https://en.wikipedia.org/wiki/Ramsey%27s_theorem#Small_Ramsey_numbers
+        assert_eq!(r(k(3), k(3)), Some(6));
+        assert_eq!(r(k(3), k(4)), Some(9));
+        assert_eq!(r(k(4), k(3)), Some(9));
+        assert_eq!(r(families::cycle(4), families::cycle(4)), Some(6));
+        assert_eq!(r(k(1), k(5)), Some(1));
+        assert_eq!(r(k(2), k(5)), Some(5));
+        assert_eq!(r(families::path(3), families::path(3)), Some(3));
+        assert_eq!(ramsey(&k(4), &k(4), 6), None);
+        for (sub, co) in [(k(3), k(4)), (families::cycle(4), families::cycle(4)), (k(2), k(2))] {
+            let crit = ramsey(&sub, &co, 9).unwrap().critical.unwrap();
+            assert!(avoids(&crit, &sub, &co));
+        }
+    }
+}
@@ -0,0 +1,242 @@
+/*!
+    Random graphs beyond the labelled `base::random_graph`.
+*/
+
+use crate::base::{BitNum,Graph,Bits};
+use crate::perm::{Perm,partitions,class_size};
+use crate::tools;
+use crate::enumerate;
+use rand::Rng;
+use rand::distributions::WeightedIndex;
+use rand_distr::Distribution;
+
+struct CycleClass {
+    orbits: Vec<Vec<usize>>,
+    // ways[i][t]: subsets of orbits i.. covering exactly t pairs
+    ways: Vec<Vec<u128>>,
+}
+
+/// Uniform distribution on isomorphism classes of graphs, optionally with a
+/// fixed number of edges, by the Dixon–Wilf method: pick a cycle type with
+/// probability proportional to the number of graphs fixed by its
+/// permutations, then a uniform graph fixed by one of them.
+pub struct Unlabelled {
+    size: usize,
+    edges: Option<usize>,
+    classes: Vec<CycleClass>,
+    index: WeightedIndex<f64>,
+}
+
+impl Unlabelled {
+    pub fn new(size: usize, edges: Option<usize>) -> Option<Self> {
+        if size == 0 { return None }
+        let target = edges.unwrap_or(0);
+        let mut classes = Vec::new();
+        let mut weights = Vec::new();
+        for cycles in partitions(size) {
+            let orbits = tools::pair_orbits(&Perm::from_cycle_type(size, &cycles));
+            let mut ways = vec![vec![0; target + 1]; orbits.len() + 1];
+            ways[orbits.len()][0] = 1;
+            for i in (0..orbits.len()).rev() {
+                for t in 0..=target {
+                    let len = orbits[i].len();
+                    ways[i][t] = ways[i + 1][t] + if t >= len { ways[i + 1][t - len] } else { 0 };
+                }
+            }
+            let fixed = match edges {
+                Some(_) => ways[0][target] as f64,
+                None => (orbits.len() as f64).exp2(),
+            };
+            weights.push(class_size(&cycles) as f64 * fixed);
+            classes.push(CycleClass { orbits, ways });
+        }
+        let index = WeightedIndex::new(weights).ok()?;
+        Some(Unlabelled { size, edges, classes, index })
+    }
+}
+
+impl Distribution<Graph> for Unlabelled {
+    fn sample<R>(&self, rng: &mut R) -> Graph where R: Rng + ?Sized {
+        let class = &self.classes[self.index.sample(rng)];
+        let mut bits: BitNum = 0;
+        let mut left = self.edges.unwrap_or(0);
+        for (i, orbit) in class.orbits.iter().enumerate() {
+            let take = match self.edges {
+                None => rng.gen(),
+                Some(_) => {
+                    let with = if left >= orbit.len() { class.ways[i + 1][left - orbit.len()] } else { 0 };
+                    rng.gen_range(0..class.ways[i][left]) < with
+                },
+            };
+            if take {
+                for &ix in orbit { bits |= 1 << ix }
+                if self.edges.is_some() { left -= orbit.len() }
+            }
+        }
+        enumerate::to_best(&Graph::from_bits(self.size, bits))
+    }
+}
+
+// Each edge with probability p, which must be in [0, 1]
+pub fn gnp(rng: &mut impl Rng, size: usize, p: f64) -> Graph {
+    let bits = (0..Graph::triangle(size)).filter(|_| rng.gen_bool(p)).fold(0, |b: BitNum, ix| b | 1 << ix);
+    Graph::from_bits(size, bits)
+}
+
+pub fn gnm(rng: &mut impl Rng, size: usize, edges: usize) -> Option<Graph> {
+    let tri = Graph::triangle(size);
+    if edges > tri { return None }
+    let bits = rand::seq::index::sample(rng, tri, edges).iter().fold(0, |b: BitNum, ix| b | 1 << ix);
+    Some(Graph::from_bits(size, bits))
+}
+
+// Pairs up degree slots at random, avoiding loops and repeated edges, and
+// starts over when stuck (Steger–Wormald).
+pub fn regular(rng: &mut impl Rng, size: usize, degree: usize) -> Option<Graph> {
+    if degree >= size.max(1) || (size * degree) % 2 == 1 { return None }
+    'retry: loop {
+        let mut slots: Vec<usize> = (0..size).flat_map(|v| std::iter::repeat_n(v, degree)).collect();
+        let mut gr = Graph::from_bits(size, 0);
+        while !slots.is_empty() {
+            let ok = |gr: &Graph, i: usize, j: usize| slots[i] != slots[j] && !gr.has_edge(slots[i], slots[j]);
+            if !(0..slots.len()).any(|i| (i + 1..slots.len()).any(|j| ok(&gr, i, j))) {
+                continue 'retry
+            }
+            loop {
+                let i = rng.gen_range(0..slots.len());
+                let j = rng.gen_range(0..slots.len());
+                if ok(&gr, i, j) {
+                    gr.edges.set((slots[i], slots[j]));
+                    slots.swap_remove(i.max(j));
+                    slots.swap_remove(i.min(j));
+                    break;
+                }
+            }
+        }
+        return Some(gr);
+    }
+}
+
+pub fn circulant(rng: &mut impl Rng, size: usize) -> Graph {
+    let jumps: Vec<usize> = (1..=size / 2).filter(|_| rng.gen()).collect();
+    Graph::from_fn(size, |a, b| jumps.contains(&(b - a)) || jumps.contains(&(size - (b - a))))
+}
+
+// Groups of a given order, as multiplication on 0..order
+#[derive(Clone, Copy, Debug)]
+enum Group {
+    Cyclic(usize),
+    // Z_a × Z_b with a dividing b
+    Product(usize, usize),
+    // Symmetries of an m-gon: r^i s^e is e*m + i
+    Dihedral(usize),
+}
+
+impl Group {
+    fn groups(order: usize) -> Vec<Group> {
+        let mut groups = vec![Group::Cyclic(order)];
+        for a in 2..order {
+            if a * a <= order && order.is_multiple_of(a) && (order / a).is_multiple_of(a) {
+                groups.push(Group::Product(a, order / a));
+            }
+        }
+        if order >= 6 && order.is_multiple_of(2) { groups.push(Group::Dihedral(order / 2)) }
+        groups
+    }
+    fn order(self) -> usize {
+        match self {
+            Group::Cyclic(n) => n,
+            Group::Product(a, b) => a * b,
+            Group::Dihedral(m) => 2 * m,
+        }
+    }
+    fn mul(self, x: usize, y: usize) -> usize {
+        match self {
+            Group::Cyclic(n) => (x + y) % n,
+            Group::Product(a, b) => ((x / b + y / b) % a) * b + (x + y) % b,
+            Group::Dihedral(m) => {
+                let (i, e) = (x % m, x / m);
+                let (k, f) = (y % m, y / m);
+                let k = if e == 1 { m - k } else { k };
+                ((e + f) % 2) * m + (i + k) % m
+            },
+        }
+    }
+    fn inverse(self, x: usize) -> usize {
+        (0..self.order()).find(|&y| self.mul(x, y) == 0).unwrap()
+    }
+}
+
+// Cayley graph of a random group of the given order, with a random
+// inverse-closed connection set.
+pub fn cayley(rng: &mut impl Rng, size: usize) -> Graph {
+    use rand::seq::SliceRandom;
+    let group = *Group::groups(size).choose(rng).unwrap();
+    let mut conn = vec![false; size];
+    for g in 1..size {
+        let h = group.inverse(g);
+        if g <= h { conn[g] = rng.gen(); conn[h] = conn[g]; }
+    }
+    Graph::from_fn(size, |a, b| conn[group.mul(group.inverse(a), b)])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use std::collections::BTreeMap;
+
+    fn tally(size: usize, edges: Option<usize>, samples: usize) -> BTreeMap<BitNum, usize> {
+        let rng = &mut rand::rngs::StdRng::seed_from_u64(29);
+        let dist = Unlabelled::new(size, edges).unwrap();
+        let mut counts = BTreeMap::new();
+        for _ in 0..samples {
+            *counts.entry(dist.sample(rng).bits()).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn test_models() {
+        let rng = &mut rand::rngs::StdRng::seed_from_u64(30);
+        for size in 1..=12 {
+            let m = rng.gen_range(0..=Graph::triangle(size));
+            assert_eq!(gnm(rng, size, m).unwrap().edge_count(), m);
+            let d = rng.gen_range(0..size);
+            match regular(rng, size, d) {
+                Some(gr) => assert!((0..size).all(|v| gr.degree_of(v) == d)),
+                None => assert_eq!(size * d % 2, 1),
+            }
+            for gr in [circulant(rng, size), cayley(rng, size)] {
+                let d = gr.degree_of(0);
+                assert!((0..size).all(|v| gr.degree_of(v) == d), "{}", gr);
+            }
+        }
+        for order in 1..=16 {
+            for group in Group::groups(order) {
+                for x in 0..order {
+                    assert_eq!(group.mul(x, 0), x);
+                    assert_eq!(group.mul(0, x), x);
+                    for y in 0..order {
+                        for z in 0..order {
+                            assert_eq!(group.mul(group.mul(x, y), z), group.mul(x, group.mul(y, z)));
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_unlabelled() {
+        let counts = tally(4, None, 22_000);
+        assert_eq!(counts.len(), 11);
+        assert!(counts.values().all(|&c| (1800..2200).contains(&c)), "{:?}", counts);
+        let counts = tally(5, Some(5), 12_000);
+        // https://oeis.org/A008406: 6 graphs on 5 vertices with 5 edges
+        assert_eq!(counts.len(), 6);
+        assert!(counts.keys().all(|&bits| bits.count_ones() == 5));
+        assert!(counts.values().all(|&c| (1800..2200).contains(&c)), "{:?}", counts);
+        assert!(Unlabelled::new(4, Some(7)).is_none());
+    }
+}
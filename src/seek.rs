@@ -7,10 +7,14 @@ use std::sync::Mutex;
 use rand::Rng;
 use rand::thread_rng;
 
+// A set of graphs closed under removing edges, such as a monotone class
+pub type Within<'a> = &'a (dyn Fn(&Graph) -> bool + Sync);
+
+// Looking for a graph not covered by the target
 struct Fixed<'a> {
-    gr: &'a Graph,
+    target: &'a tools::Target,
+    within: Option<Within<'a>>,
     seen: &'a Mutex<BTreeSet<BitNum>>,
-    row: &'a Vec<(usize, usize)>,
     bailout: usize,
     rng: rand::rngs::ThreadRng,
     // Size limitations only seem to slow things down.
@@ -20,7 +24,7 @@ struct Fixed<'a> {
 
 fn recurse(fixed: &mut Fixed, ce: Graph) -> Option<Graph> {
     // eprintln!("recurse: {} {:?}", grc, grc);
-    match tools::find_subgraph_ss(fixed.gr, fixed.row, &ce.complement()) {
+    match fixed.target.find_in_complement(&ce) {
         Some(grtw) => {
             // if ce.bits().count_ones() == fixed.top_size { return None }
             let hi = tools::hi_bit_ix(grtw.bits()) + 1;
@@ -33,9 +37,10 @@ fn recurse(fixed: &mut Fixed, ce: Graph) -> Option<Graph> {
                 if grtw.bits() & bit == 0 { continue }
                 let grnext = ce.bits() | bit;
                 // eprintln!("{}: {} -> {}", b, grtw, grnext);
-                let grnext = Graph::from_bits(fixed.gr.size, grnext);
+                let grnext = Graph::from_bits(fixed.target.size, grnext);
                 // eprintln!("1 {}: {} -> {}; {}", b, grtw, grnext, fixed.gr);
-                if tools::isso_inner(fixed.gr, fixed.row, &grnext) { continue }
+                if fixed.within.is_some_and(|within| !within(&grnext)) { continue }
+                if fixed.target.in_graph(&grnext) { continue }
                 // eprintln!("2 {}: {} -> {}; {}", b, grtw, grnext, fixed.gr);
                 let grnext = enumerate::to_best(&grnext);
                 {
@@ -43,7 +48,7 @@ fn recurse(fixed: &mut Fixed, ce: Graph) -> Option<Graph> {
                     if seen.contains(&0) { return None }
                     if !seen.insert(grnext.bits()) { continue }
                     if seen.len() >= fixed.bailout { return None }
-                    if seen.len() % 100_000 == 0 {
+                    if seen.len().is_multiple_of(100_000) {
                         eprint!("\n Checked {}\r", seen.len());
                     }
                 }
@@ -61,7 +66,19 @@ fn recurse(fixed: &mut Fixed, ce: Graph) -> Option<Graph> {
     }
 }
 
-pub fn seek(gr: &Graph, bailout: usize) -> (Option<Graph>, usize) {
+pub fn seek(gr: &Graph, bailout: usize) -> (Option<Graph>, usize) { seek_pair(gr, gr, bailout) }
+
+// Counterexample for the pair: without gr, and with complement without co
+pub fn seek_pair(gr: &Graph, co: &Graph, bailout: usize) -> (Option<Graph>, usize) {
+    seek_target(&tools::Target::pair(gr, co), bailout, None)
+}
+
+// With within, only counterexamples in it are found.  The search only adds
+// edges, so it can stay within a set closed under removing edges, but not
+// within others, such as regular graphs, which it would seldom reach.
+pub fn seek_target(target: &tools::Target, bailout: usize, within: Option<Within>) -> (Option<Graph>, usize) {
+    // An edgeless graph is in everything
+    if target.subs.iter().chain(&target.cos).any(|(gr, _)| gr.bits() == 0) { return (None, 0) }
     let seen = &Mutex::new(BTreeSet::new());
     /*
     for grm in tools::bump(&gr, false) {
@@ -69,15 +86,24 @@ pub fn seek(gr: &Graph, bailout: usize) -> (Option<Graph>, usize) {
         eprintln!("seek: {} / {} {}", grm, grm.show_bits(), tools::count_symmetries(&grm));
     }
     */
-    let rets = tools::bump(gr, false);
+    // Start from retracts of the graphs, which for a single graph cannot
+    // contain it
+    let mut rets: BTreeSet<_> = target.subs.iter().flat_map(|(gr, _)| tools::bump(gr, false))
+        .filter(|&grm| !target.in_graph(&Graph::from_bits(target.size, grm))).collect();
+    // A search cannot come back once it leaves, so it starts inside, from
+    // the edgeless graph if need be
+    if let Some(within) = within {
+        rets.retain(|&grm| within(&Graph::from_bits(target.size, grm)));
+        rets.insert(0);
+    }
     // let rets: Vec<_> = rets.iter().cloned().rev().collect();
     let res = rets.par_iter().find_map_any(|grm| {
-        let grm = Graph::from_bits(gr.size, *grm);
+        let grm = Graph::from_bits(target.size, *grm);
         // eprintln!("seek: {:?} / {}", grm, tools::count_symmetries(&grm));
         recurse(&mut Fixed {
-                gr,
+                target,
+                within,
                 seen,
-                row: &tools::build_sorted_row(gr),
                 bailout,
                 rng: thread_rng(),
                 // top_size: (Graph::triangle(gr.size) as u32 + 1) / 2,
@@ -0,0 +1,92 @@
+/*!
+    Self-complementary graphs, generated from the cycle structure of an
+    antimorphism (an isomorphism from a graph to its complement).
+*/
+
+use crate::base::{BitNum,Graph,Bits};
+use crate::perm::Perm;
+use crate::tools;
+use crate::enumerate;
+use std::collections::BTreeSet;
+
+// An antimorphism may be taken to have cycles all of length a power of two
+// at least 4, plus a fixed point when size ≡ 1 (mod 4): an odd power of an
+// antimorphism is still one, so the odd parts of the cycle lengths can be
+// removed.
+fn cycle_types(total: usize, max: usize) -> Vec<Vec<usize>> {
+    if total == 0 { return vec![Vec::new()] }
+    let mut types = Vec::new();
+    let mut len = 4;
+    while len <= max.min(total) {
+        for mut rest in cycle_types(total - len, len) {
+            rest.insert(0, len);
+            types.push(rest);
+        }
+        len *= 2;
+    }
+    types
+}
+
+/// All self-complementary graphs on `size` vertices, in canonical form.
+pub fn self_complementary(size: usize) -> BTreeSet<BitNum> {
+    let mut seen = BTreeSet::new();
+    if size == 0 || size % 4 >= 2 { return seen }
+    for cycles in cycle_types(size - size % 4, size) {
+        let orbits = tools::pair_orbits(&Perm::from_cycle_type(size, &cycles));
+        // Each orbit alternates between edges and non-edges; the choice for
+        // the first orbit only decides between the graph and its complement.
+        for choice in 0 .. 1 << orbits.len().saturating_sub(1) as BitNum {
+            let mut bits: BitNum = 0;
+            for (i, orbit) in orbits.iter().enumerate() {
+                let parity = if i == 0 { 0 } else { (choice >> (i - 1)) as usize & 1 };
+                for &ix in orbit.iter().skip(parity).step_by(2) {
+                    bits |= 1 << ix;
+                }
+            }
+            seen.insert(enumerate::to_best(&Graph::from_bits(size, bits)).bits());
+        }
+    }
+    seen
+}
+
+/// An antimorphism `p` of the graph, with `gr.renumber(&p) == gr.complement()`,
+/// if there is one.
+pub fn is_self_complementary(gr: &Graph) -> Option<Perm> {
+    if 2 * gr.edge_count() != Graph::triangle(gr.size) { return None }
+    let sorted = tools::build_sorted_row(gr);
+    tools::isso_inner::<Option<Perm>>(gr, &sorted, &gr.complement()).map(|p| p.inverse())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+    use crate::base::random_graph;
+
+    #[test]
+    fn test_counts() {
+        // https://oeis.org/A000171
+        for (size, count) in [(1, 1), (2, 0), (3, 0), (4, 1), (5, 2), (8, 10), (9, 36)] {
+            let all = self_complementary(size);
+            assert_eq!(all.len(), count, "size = {}", size);
+            for bits in all {
+                let gr = Graph::from_bits(size, bits);
+                assert!(enumerate::is_best(&gr));
+                let p = is_self_complementary(&gr).unwrap();
+                assert_eq!(gr.renumber(&p), gr.complement());
+            }
+        }
+    }
+
+    #[test]
+    fn test_predicate() {
+        let rng = &mut rand::thread_rng();
+        for _ in 0 .. 2000 {
+            let size = rng.gen_range(1..=8);
+            let gr = random_graph(rng, size);
+            let sc = tools::is_subgraph_of(&gr, &gr.complement())
+                && 2 * gr.edge_count() == Graph::triangle(size);
+            assert_eq!(is_self_complementary(&gr).is_some(), sc, "{}", gr.bits());
+        }
+    }
+}
@@ -0,0 +1,73 @@
+/*!
+    The least n for which a graph, padded with isolated vertices, is a
+    universal ingraph for n.  Being universal for n implies it for n + 1, so
+    this is found by counting up: cheap reasons and `seek` rule out n where
+    they can, and the answer is confirmed against all graphs on n vertices.
+*/
+
+use crate::base::{BitNum,Graph,Bits};
+use crate::tools;
+use crate::enumerate;
+use crate::seek;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Threshold {
+    pub n: usize,
+    // Neither it nor its complement contains the graph; None when the graph
+    // has more than n - 1 vertices.
+    pub counterexample: Option<Graph>,
+}
+
+/// The threshold of `gr`, if it is at most `max`; `all(n)` gives all graphs
+/// on n vertices and `bailout` limits each seek.
+pub fn threshold(gr: &Graph, max: usize, bailout: usize, all: impl Fn(usize) -> Vec<BitNum>)
+        -> Option<Threshold> {
+    let bits = gr.bits();
+    let edges = bits.count_ones() as usize;
+    let mut counterexample = None;
+    for n in tools::infer_size(bits).max(1)..=max {
+        let tri = Graph::triangle(n);
+        let sub = Graph::from_bits(n, bits);
+        // Some graph and its complement both have at most ceil(tri / 2) edges
+        if edges > tri.div_ceil(2) {
+            counterexample = Some(enumerate::to_best(&Graph::from_bits(n, tools::one_bits(tri / 2))));
+            continue
+        }
+        if let (Some(ce), _) = seek::seek(&sub, bailout) {
+            counterexample = Some(ce);
+            continue
+        }
+        let counter = tools::noncovers(all(n).into_iter(), &sub).next();
+        match counter {
+            Some(ce) => counterexample = Some(Graph::from_bits(n, ce)),
+            None => return Some(Threshold { n, counterexample }),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::catalog;
+
+    #[test]
+    fn test_threshold() {
+        let find = |size, bits| threshold(&Graph::from_bits(size, bits), 7, 10_000, enumerate::all_graphs);
+        assert_eq!(find(2, 1), Some(Threshold { n: 2, counterexample: None }));
+        // R(3, 3) = 6, and C5 is the counterexample
+        let t = find(3, 7).unwrap();
+        assert_eq!(t.n, 6);
+        assert_eq!(t.counterexample, Some(enumerate::to_best(&Graph::from_fn(5, |a, b| b - a == 1 || b - a == 4))));
+        assert_eq!(find(4, 63), None);
+        for entry in catalog::CATALOG.iter().filter(|e| e.n <= 7) {
+            let t = find(entry.n, entry.bits).unwrap();
+            assert_eq!(t.n, entry.n);
+            assert_eq!(t.counterexample.is_none(), entry.vertices() == entry.n);
+            if let Some(ce) = t.counterexample {
+                let sub = Graph::from_bits(ce.size, entry.bits);
+                assert!(!tools::is_subgraph_of(&sub, &ce) && !tools::is_subgraph_of(&sub, &ce.complement()));
+            }
+        }
+    }
+}
@@ -1,16 +1,16 @@
-/**
+/*!
     Permutations and basic operations on them.
 
     This is extracted from a larger module in another project.
 */
 
-use crate::base::{BitNum,Graph,Bits,rev_hi_index};
-use crate::perm::{Perm,all_perms};
+use crate::base::{BitNum,Graph,Bits,rev_hi_index,rev_index,index};
+use crate::perm::{self,Perm,all_perms,partitions,class_size};
 use crate::enumerate;
 use std::time::SystemTime;
 use utc_dt::UTCDatetime;
 use fix_fn::fix_fn;
-use std::collections::BTreeSet;
+use std::collections::{BTreeMap,BTreeSet};
 
 #[inline]
 pub fn factorial(n: usize) -> usize {
@@ -53,17 +53,48 @@ pub fn infer_graph(edges: BitNum) -> Graph {
     Graph::from_bits(infer_size(edges), edges)
 }
 
-pub fn read_graphs<B: Bits>(sz: usize, path: &str) -> impl Iterator<Item=B> {
+// Lines up to any comma, from a file or - for standard input
+fn try_read_lines(path: &str) -> Result<impl Iterator<Item=Result<String, String>> + use<>, String> {
     use std::fs::File;
     use std::io::{BufReader,BufRead};
-    let file = File::open(path).unwrap();
-    let reader = BufReader::new(file);
-    reader.lines().map(move |line| {
-        let line = line.unwrap();
-        let line = line.split_once(',').map_or(line.as_str(), |x| x.0);
-        let edges = line.parse::<BitNum>().unwrap();
-        B::from_bits(sz, edges)
-    })
+    let reader: Box<dyn BufRead> = if path == "-" {
+        Box::new(BufReader::new(std::io::stdin()))
+    } else {
+        Box::new(BufReader::new(File::open(path).map_err(|e| format!("{}: {}", path, e))?))
+    };
+    let path = path.to_string();
+    Ok(reader.lines().map(move |line| {
+        let mut line = line.map_err(|e| format!("{}: {}", path, e))?;
+        if let Some(ix) = line.find(',') { line.truncate(ix) }
+        Ok(line)
+    }))
+}
+
+fn read_lines(path: &str) -> impl Iterator<Item=String> {
+    try_read_lines(path).unwrap_or_else(|e| panic!("{}", e)).map(|line| line.unwrap_or_else(|e| panic!("{}", e)))
+}
+
+pub fn read_graphs<B: Bits>(sz: usize, path: &str) -> impl Iterator<Item=B> {
+    read_lines(path).map(move |line| B::from_bits(sz, line.parse::<BitNum>().unwrap()))
+}
+
+// As read_graphs, with errors for the caller
+pub fn try_read_graphs<B: Bits>(sz: usize, path: &str) -> Result<impl Iterator<Item=Result<B, String>> + use<B>, String> {
+    Ok(try_read_lines(path)?.map(move |line| {
+        let line = line?;
+        line.parse::<BitNum>().map(|bits| B::from_bits(sz, bits)).map_err(|_| format!("Bad graph: {}", line))
+    }))
+}
+
+// One family per line, its graphs separated by spaces; blank lines are skipped.
+// Each graph is bits or a family expression, as for any graph argument.
+pub fn read_families(sz: usize, path: &str) -> Result<impl Iterator<Item=Result<Target, String>> + use<>, String> {
+    Ok(try_read_lines(path)?.filter(|line| line.as_ref().map_or(true, |line| !line.trim().is_empty())).map(move |line| {
+        let grs = line?.split_whitespace()
+            .map(|s| crate::families::parse_bits(s).and_then(|arg| arg.on(sz)).map(|bits| Graph::from_bits(sz, bits)))
+            .collect::<Result<Vec<Graph>, String>>()?;
+        Ok(Target::family(&grs))
+    }))
 }
 
 pub fn count_symmetries_slow(gr: &Graph) -> usize {
@@ -109,6 +140,48 @@ pub fn count_symmetries(gr: &Graph) -> usize {
         * factorial(degs[0].len()) * factorial(degs[gr.size - 1].len())
 }
 
+// Orbits of a permutation acting on vertex pairs, as edge bit indices
+pub fn pair_orbits(p: &Perm) -> Vec<Vec<usize>> {
+    let mut done = vec![false; Graph::triangle(p.size())];
+    let mut orbits = Vec::new();
+    for ix in 0..done.len() {
+        if done[ix] { continue }
+        let mut orbit = Vec::new();
+        let mut cur = ix;
+        while !done[cur] {
+            done[cur] = true;
+            orbit.push(cur);
+            let (a, b) = rev_index(cur);
+            cur = index(p.apply(a), p.apply(b));
+        }
+        orbits.push(orbit);
+    }
+    orbits
+}
+
+// Number of graphs on size vertices with each number of edges, by Pólya
+// counting; None if the arithmetic overflows, which it does not up to
+// MAX_SIZE.
+pub fn graph_counts(size: usize) -> Option<Vec<u128>> {
+    let tri = Graph::triangle(size);
+    let mut total = vec![0u128; tri + 1];
+    for cycles in partitions(size) {
+        let mut poly = vec![0u128; tri + 1];
+        poly[0] = 1;
+        for orbit in pair_orbits(&Perm::from_cycle_type(size, &cycles)) {
+            for m in (orbit.len()..=tri).rev() {
+                poly[m] += poly[m - orbit.len()];
+            }
+        }
+        let count = class_size(&cycles);
+        for (t, p) in total.iter_mut().zip(poly) {
+            *t = t.checked_add(p.checked_mul(count)?)?;
+        }
+    }
+    let fac = factorial(size) as u128;
+    Some(total.into_iter().map(|t| t / fac).collect())
+}
+
 const UNFILLED: usize = 0xfffff;
 
 pub trait IIResult {
@@ -126,6 +199,7 @@ impl IIResult for Option<Perm> {
     fn failure() -> Self { None }
 }
 
+#[allow(clippy::needless_range_loop)]
 pub fn isso_inner<T: IIResult>(sub: &Graph, sub_sorted: &[(usize, usize)], sup: &Graph) -> T {
     let size = sub.size;
     let sup_row = degree_row(sup);
@@ -165,17 +239,152 @@ pub fn build_sorted_row(gr: &Graph) -> Vec<(usize, usize)> {
 }
 
 pub fn ingraph_check(sup: &Graph, sub_sorted: &[(usize, usize)], sub: &Graph) -> bool {
+    pair_check(sup, sub_sorted, sub, sub_sorted, sub)
+}
+
+// Off-diagonal ingraph_check: sub in sup, or co in its complement
+pub fn pair_check(sup: &Graph, sub_sorted: &[(usize, usize)], sub: &Graph,
+        co_sorted: &[(usize, usize)], co: &Graph) -> bool {
     let isup = sup.complement();
-    isso_inner(sub, sub_sorted, &isup) || isso_inner(sub, sub_sorted, sup)
+    isso_inner(co, co_sorted, &isup) || isso_inner(sub, sub_sorted, sup)
 }
 
 pub fn noncovers<B: Bits + Copy, V: Iterator<Item=B>>(sups: V, sub: &Graph)
         -> impl Iterator<Item=B> + use<'_, B, V> {
-    let sub_sorted = build_sorted_row(sub);
-    let min_edges = (Graph::triangle(sub.size) + 1) / 2;
-    sups.filter(
-        move |sup| sup.bits().count_ones() as usize >= min_edges
-            && !ingraph_check(&Graph::from_bits(sub.size, sup.bits()), &sub_sorted, sub))
+    noncovers_pair(sups, sub, sub)
+}
+
+// Every graph not covered, complements included, in canonical form by edge count
+pub fn misses_by_edges(sups: impl Iterator<Item=BitNum>, sub: &Graph) -> BTreeMap<usize, Vec<Graph>> {
+    let target = Target::pair(sub, sub);
+    let mut groups = BTreeMap::new();
+    for sup in sups {
+        let sup = Graph::from_bits(sub.size, sup);
+        if target.covers(&sup) { continue }
+        groups.entry(sup.edge_count()).or_insert_with(Vec::new).push(enumerate::to_best(&sup));
+    }
+    for group in groups.values_mut() { group.sort(); group.dedup() }
+    groups
+}
+
+// Graphs without sub, whose complements are without co
+pub fn noncovers_pair<B: Bits + Copy, V: Iterator<Item=B>>(sups: V, sub: &Graph, co: &Graph)
+        -> impl Iterator<Item=B> + use<B, V> {
+    noncovers_target(sups, Target::pair(sub, co))
+}
+
+// Graphs the target does not cover
+pub fn noncovers_target<B: Bits + Copy, V: Iterator<Item=B>>(sups: V, target: Target)
+        -> impl Iterator<Item=B> + use<B, V> {
+    noncovers_within(sups, target, |_| true, true)
+}
+
+// Only graphs that keep accepts count, closed saying whether it accepts the
+// complements of those it accepts
+pub fn noncovers_within<B: Bits + Copy, V: Iterator<Item=B>, K: Fn(&Graph) -> bool>(sups: V, target: Target, keep: K, closed: bool)
+        -> impl Iterator<Item=B> + use<B, V, K> {
+    // When the same graphs are looked for in both, a graph and its
+    // complement are equally good, so only one of them, the one with more
+    // edges, need be checked.
+    let min_edges = if target.is_symmetric() && closed { Graph::triangle(target.size).div_ceil(2) } else { 0 };
+    sups.filter(move |sup| {
+        let sup = Graph::from_bits(target.size, sup.bits());
+        sup.edge_count() >= min_edges && keep(&sup) && !target.covers(&sup)
+    })
+}
+
+/// Graphs to look for: some sub in a graph, or some co in its complement.
+/// A family of ingraphs has the same graphs for both.  Induced targets take
+/// the graphs on an explicit number of vertices, isolated ones included.
+#[derive(Clone, Debug)]
+pub struct Target {
+    pub size: usize,
+    pub subs: Vec<(Graph, Vec<(usize, usize)>)>,
+    pub cos: Vec<(Graph, Vec<(usize, usize)>)>,
+    pub induced: bool,
+}
+
+impl Target {
+    pub fn new(subs: &[Graph], cos: &[Graph]) -> Self {
+        let with_rows = |grs: &[Graph]| grs.iter().map(|gr| (gr.clone(), build_sorted_row(gr))).collect();
+        Target { size: subs[0].size, subs: with_rows(subs), cos: with_rows(cos), induced: false }
+    }
+    pub fn pair(sub: &Graph, co: &Graph) -> Self { Target::new(std::slice::from_ref(sub), std::slice::from_ref(co)) }
+    pub fn family(grs: &[Graph]) -> Self { Target::new(grs, grs) }
+    // Induced copies of the graphs on vertices 0..vertices, which must hold
+    // all their edges; None keeps the ordinary subgraph checks.
+    pub fn with_induced(self, vertices: Option<usize>) -> Self {
+        let Some(vertices) = vertices else { return self };
+        let restrict = |grs: Vec<(Graph, Vec<(usize, usize)>)>| grs.into_iter().map(|(gr, _)| {
+            assert!(infer_size(gr.bits()) <= vertices, "Graph {} needs more than {} vertices", gr, vertices);
+            let gr = Graph::from_bits(vertices, gr.bits());
+            let row = build_sorted_row(&gr);
+            (gr, row)
+        }).collect();
+        Target { size: self.size, subs: restrict(self.subs), cos: restrict(self.cos), induced: true }
+    }
+    pub fn is_symmetric(&self) -> bool { self.subs == self.cos }
+    fn contains(&self, (sub, row): &(Graph, Vec<(usize, usize)>), sup: &Graph) -> bool {
+        if self.induced { find_induced(sub, row, sup).is_some() } else { isso_inner(sub, row, sup) }
+    }
+    pub fn in_graph(&self, sup: &Graph) -> bool {
+        self.subs.iter().any(|sub| self.contains(sub, sup))
+    }
+    // A copy of some co in the complement of sup
+    pub fn find_in_complement(&self, sup: &Graph) -> Option<Graph> {
+        let isup = sup.complement();
+        self.cos.iter().find_map(|(co, row)| if self.induced {
+            find_induced(co, row, &isup).map(|map| {
+                let mut gr = Graph::from_bits(self.size, 0);
+                for b in 1..co.size { for a in 0..b {
+                    if co.has_edge(a, b) { gr.edges.set((map[a], map[b])) }
+                }}
+                gr
+            })
+        } else {
+            find_subgraph_ss(co, row, &isup)
+        })
+    }
+    pub fn covers(&self, sup: &Graph) -> bool {
+        let isup = sup.complement();
+        self.cos.iter().any(|co| self.contains(co, &isup)) || self.in_graph(sup)
+    }
+}
+
+// Induced copy of sub in sup, which may have more vertices: non-edges of sub
+// go to non-edges.  Gives the vertex of sup for each vertex of sub.
+pub fn find_induced(sub: &Graph, sub_sorted: &[(usize, usize)], sup: &Graph) -> Option<Vec<usize>> {
+    if sub.size > sup.size { return None }
+    let sup_row = degree_row(sup);
+    let mut image = vec![UNFILLED; sub.size];
+    let mut used = vec![false; sup.size];
+    let go = fix_fn!(|go, image: &mut Vec<usize>, used: &mut Vec<bool>, i: usize| -> bool {
+        if i == sub.size { return true }
+        let (el_deg, el) = sub_sorted[i];
+        'outer: for j in 0..sup.size {
+            if used[j] || el_deg > sup_row[j] { continue }
+            if sub.size - el_deg > sup.size - sup_row[j] { continue }
+            for &(_, v) in &sub_sorted[..i] {
+                if sub.has_edge(el, v) != sup.has_edge(j, image[v]) { continue 'outer }
+            }
+            image[el] = j;
+            used[j] = true;
+            if go(image, used, i + 1) { return true }
+            used[j] = false;
+        }
+        false
+    });
+    if go(&mut image, &mut used, 0) { Some(image) } else { None }
+}
+
+pub fn is_induced_subgraph_of(sub: &Graph, sup: &Graph) -> bool {
+    find_induced(sub, &build_sorted_row(sub), sup).is_some()
+}
+
+pub fn naive_is_induced_subgraph_of(sub: &Graph, sup: &Graph) -> bool {
+    use itertools::Itertools;
+    (0..sup.size).permutations(sub.size).any(|map|
+        (1..sub.size).all(|b| (0..b).all(|a| sub.has_edge(a, b) == sup.has_edge(map[a], map[b]))))
 }
 
 pub fn is_subgraph_of(sub: &Graph, sup: &Graph) -> bool {
@@ -191,6 +400,66 @@ pub fn find_subgraph_of(sub: &Graph, sup: &Graph) -> Option<Graph> {
     find_subgraph_ss(sub, &build_sorted_row(sub), sup)
 }
 
+// Automorphism p (gr.renumber(&p) == gr) sending each a to b for (a, b) in pins
+pub fn find_automorphism(gr: &Graph, pins: &[(usize, usize)]) -> Option<Perm> {
+    let size = gr.size;
+    let degs = degree_row(gr);
+    let order: Vec<usize> = pins.iter().map(|x| x.0)
+        .chain((0..size).filter(|v| pins.iter().all(|x| x.0 != *v)))
+        .collect();
+    let mut image = vec![UNFILLED; size];
+    let mut used = vec![false; size];
+    let go = fix_fn!(|go, image: &mut Vec<usize>, used: &mut Vec<bool>, i: usize| -> bool {
+        if i == size { return true }
+        let v = order[i];
+        let cands: Vec<usize> = match pins.get(i) {
+            Some(&(_, w)) => vec![w],
+            None => (0..size).collect(),
+        };
+        'outer: for w in cands {
+            if used[w] || degs[v] != degs[w] { continue }
+            for &u in &order[..i] {
+                if gr.has_edge(v, u) != gr.has_edge(w, image[u]) { continue 'outer }
+            }
+            image[v] = w;
+            used[w] = true;
+            if go(image, used, i + 1) { return true }
+            used[w] = false;
+        }
+        false
+    });
+    if go(&mut image, &mut used, 0) { Some(Perm::new_unsafe(image)) } else { None }
+}
+
+// Orbits of the automorphism group on vertex pairs, as edge bit indices,
+// from the generators one search finds
+pub fn edge_orbits(gr: &Graph) -> Vec<Vec<usize>> {
+    let tri = Graph::triangle(gr.size);
+    let mut parent: Vec<usize> = (0..tri).collect();
+    fn find(parent: &mut [usize], i: usize) -> usize {
+        let mut i = i;
+        while parent[i] != i { parent[i] = parent[parent[i]]; i = parent[i]; }
+        i
+    }
+    let gens = perm::automorphisms(&degree_row(gr), |a, b| gr.has_edge(a, b) as usize,
+        |order| gr.unrenumber(&Perm::new_unsafe(order.to_vec())).bits());
+    for p in gens {
+        for k in 0..tri {
+            let (x, y) = rev_index(k);
+            let (r, s) = (find(&mut parent, k), find(&mut parent, index(p.apply(x), p.apply(y))));
+            parent[r.max(s)] = r.min(s);
+        }
+    }
+    let mut orbits: Vec<Vec<usize>> = Vec::new();
+    let mut slot = vec![usize::MAX; tri];
+    for i in 0..tri {
+        let r = find(&mut parent, i);
+        if slot[r] == usize::MAX { slot[r] = orbits.len(); orbits.push(Vec::new()) }
+        orbits[slot[r]].push(i);
+    }
+    orbits
+}
+
 pub fn naive_is_subgraph_of(sub: &Graph, sup: &Graph) -> bool {
     for perm in all_perms(sup.size) {
         let sub1 = sub.unrenumber(&perm);
@@ -199,7 +468,17 @@ pub fn naive_is_subgraph_of(sub: &Graph, sup: &Graph) -> bool {
     false
 }
 
+// One change per orbit of edges (or non-edges) suffices, since changes in
+// the same orbit give isomorphic graphs.
 pub fn bump(gr: &Graph, extend: bool) -> BTreeSet<BitNum> {
+    let base = gr.bits();
+    edge_orbits(gr).iter().map(|orbit| orbit[0])
+        .filter(|&bit| (base & 1 << bit == 0) == extend)
+        .map(|bit| enumerate::to_best(&Graph::from_bits(gr.size, base ^ 1 << bit)).bits())
+        .collect()
+}
+
+pub fn naive_bump(gr: &Graph, extend: bool) -> BTreeSet<BitNum> {
     let mut seen = BTreeSet::new();
     let base = gr.bits();
     for bit in 0 .. Graph::triangle(gr.size) {
@@ -215,12 +494,196 @@ pub fn bump(gr: &Graph, extend: bool) -> BTreeSet<BitNum> {
     seen
 }
 
+// Graphs from adding `add` edges and removing `remove`, up to isomorphism.
+// Some edge changed can always be moved by an automorphism to the first of
+// its orbit, so only those need trying as the first change.
+pub fn bump_depth(gr: &Graph, add: usize, remove: usize) -> BTreeSet<BitNum> {
+    use itertools::Itertools;
+    let mut seen = BTreeSet::new();
+    let base = gr.bits();
+    let tri = Graph::triangle(gr.size);
+    let present: Vec<usize> = (0..tri).filter(|&i| base & 1 << i != 0).collect();
+    let absent: Vec<usize> = (0..tri).filter(|&i| base & 1 << i == 0).collect();
+    if add > absent.len() || remove > present.len() { return seen }
+    if add == 0 && remove == 0 {
+        seen.insert(enumerate::to_best(gr).bits());
+        return seen;
+    }
+    let firsts: Vec<usize> = edge_orbits(gr).iter().map(|o| o[0])
+        .filter(|&i| (base & 1 << i != 0) == (remove > 0)).collect();
+    let mask = |ixs: &[&usize]| ixs.iter().fold(0, |b: BitNum, &&i| b | 1 << i);
+    for first in firsts {
+        let (rest_remove, rest_add) = if remove > 0 { (remove - 1, add) } else { (0, add - 1) };
+        for rs in present.iter().filter(|&&i| i != first).combinations(rest_remove) {
+            for adds in absent.iter().filter(|&&i| i != first).combinations(rest_add) {
+                let val = (base & !mask(&rs) | mask(&adds)) ^ (1 << first);
+                seen.insert(enumerate::to_best(&Graph::from_bits(gr.size, val)).bits());
+            }
+        }
+    }
+    seen
+}
+
+// Like bump, but deleting a vertex, or adding one with any neighbourhood
+pub fn bump_vertex(gr: &Graph, extend: bool) -> BTreeSet<BitNum> {
+    let mut seen = BTreeSet::new();
+    if extend {
+        for nbrs in 0 .. 1usize << gr.size {
+            let nbrs: Vec<usize> = (0..gr.size).filter(|v| nbrs & 1 << v != 0).collect();
+            let Some(gr) = gr.add_vertex(&nbrs) else { break };
+            seen.insert(enumerate::to_best(&gr).bits());
+        }
+    } else if gr.size > 1 {
+        for v in 0..gr.size {
+            seen.insert(enumerate::to_best(&gr.delete_vertex(v)).bits());
+        }
+    }
+    seen
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use rand::Rng;
     use crate::base::random_graph;
     #[test]
+    fn test_automorphism() {
+        let rng = &mut rand::thread_rng();
+        for _ in 0 .. 300 {
+            let size = rng.gen_range(1..=6);
+            let gr = random_graph(rng, size);
+            let a = rng.gen_range(0..size);
+            let b = rng.gen_range(0..size);
+            let found = find_automorphism(&gr, &[(a, b)]);
+            let naive = all_perms(size).any(|p| p.apply(a) == b && gr.renumber(&p) == gr);
+            assert_eq!(found.is_some(), naive, "{} {} {}", gr.bits(), a, b);
+            if let Some(p) = found {
+                assert_eq!(p.apply(a), b);
+                assert_eq!(gr.renumber(&p), gr);
+            }
+        }
+    }
+    #[test]
+    fn test_misses() {
+        let k3 = |n| Graph::from_bits(n, 7);
+        let c5 = enumerate::to_best(&Graph::from_fn(5, |a, b| b - a == 1 || b - a == 4));
+        let all5: Vec<BitNum> = crate::enumerate::all_graphs(5);
+        assert_eq!(misses_by_edges(all5.iter().cloned(), &k3(5)), BTreeMap::from([(5, vec![c5])]));
+        // On 4 vertices, complements pair up across the middle
+        let all4 = crate::enumerate::all_graphs(4);
+        let groups = misses_by_edges(all4.iter().cloned(), &k3(4));
+        assert_eq!(groups.keys().cloned().collect::<Vec<_>>(), vec![2, 3, 4]);
+        assert_eq!(groups[&2].len(), groups[&4].len());
+        assert!(groups.iter().all(|(&e, grs)| grs.iter().all(|gr| gr.edge_count() == e)));
+        assert_eq!(noncovers(all4.iter().cloned(), &k3(4)).count(), groups[&3].len() + groups[&4].len());
+        assert!(misses_by_edges(crate::enumerate::all_graphs(6).into_iter(), &k3(6)).is_empty());
+    }
+    #[test]
+    fn test_read_families() {
+        let path = std::env::temp_dir().join(format!("families-test-{}", std::process::id()));
+        std::fs::write(&path, "7\n\n  \n11 7,comment\ncycle(4) 3\n").unwrap();
+        let sizes: Vec<usize> = read_families(5, path.to_str().unwrap()).unwrap().map(|t| t.unwrap().subs.len()).collect();
+        assert_eq!(sizes, vec![1, 2, 2]);
+        for bad in ["7 x", "cycle(6)", "1024"] {
+            std::fs::write(&path, bad).unwrap();
+            assert!(read_families(5, path.to_str().unwrap()).unwrap().any(|t| t.is_err()), "{}", bad);
+        }
+        std::fs::remove_file(&path).unwrap();
+        assert!(read_families(5, path.to_str().unwrap()).is_err());
+    }
+    #[test]
+    fn test_edge_orbits() {
+        let rng = &mut rand::thread_rng();
+        for _ in 0 .. 100 {
+            let size = rng.gen_range(2..=6);
+            let gr = random_graph(rng, size);
+            let orbits = edge_orbits(&gr);
+            let autos: Vec<Perm> = all_perms(size).filter(|p| gr.renumber(p) == gr).collect();
+            for orbit in orbits.iter() {
+                let (a, b) = rev_index(orbit[0]);
+                let naive: BTreeSet<usize> = autos.iter()
+                    .map(|p| index(p.apply(a), p.apply(b))).collect();
+                assert_eq!(naive, orbit.iter().cloned().collect(), "{}", gr.bits());
+            }
+        }
+        // Symmetric graphs at the largest size: edges and non-edges by distance
+        use crate::families;
+        for (gr, count) in [(families::paley(13), 2), (families::petersen(), 2), (families::cycle(16), 8),
+                (families::complete_bipartite(8, 8), 2), (families::matching(8), 2), (families::empty(16), 1)] {
+            assert_eq!(edge_orbits(&gr).len(), count, "{}", gr);
+        }
+    }
+    #[test]
+    fn test_graph_counts() {
+        // https://oeis.org/A008406
+        assert_eq!(graph_counts(5), Some(vec![1, 1, 2, 4, 6, 6, 6, 4, 2, 1, 1]));
+        // https://oeis.org/A000088
+        let totals = [1, 1, 2, 4, 11, 34, 156, 1044, 12346, 274668, 12005168, 1018997864,
+            165091172592, 50502031367952, 29054155657235488, 31426485969804308768,
+            64001015704527557894928];
+        for (size, &total) in totals.iter().enumerate() {
+            assert_eq!(graph_counts(size).unwrap().iter().sum::<u128>(), total, "{}", size);
+        }
+    }
+    #[test]
+    fn test_bump() {
+        let rng = &mut rand::thread_rng();
+        for _ in 0 .. 200 {
+            let size = rng.gen_range(2..=8);
+            let gr = random_graph(rng, size);
+            for extend in [false, true] {
+                assert_eq!(bump(&gr, extend), naive_bump(&gr, extend), "{} {}", gr.bits(), extend);
+            }
+        }
+    }
+    #[test]
+    fn test_bump_depth() {
+        use itertools::Itertools;
+        let rng = &mut rand::thread_rng();
+        for _ in 0 .. 30 {
+            let size = rng.gen_range(2..=6);
+            let gr = random_graph(rng, size);
+            assert_eq!(bump_depth(&gr, 0, 1), bump(&gr, false));
+            assert_eq!(bump_depth(&gr, 1, 0), bump(&gr, true));
+            let tri = Graph::triangle(size);
+            let present: Vec<usize> = (0..tri).filter(|&i| gr.edges.0.get(i)).collect();
+            let absent: Vec<usize> = (0..tri).filter(|&i| !gr.edges.0.get(i)).collect();
+            for (add, remove) in [(2, 0), (0, 2), (1, 1), (2, 1)] {
+                let mut naive = BTreeSet::new();
+                for rs in present.iter().combinations(remove) {
+                    for adds in absent.iter().combinations(add) {
+                        let mut val = gr.bits();
+                        for &&i in &rs { val &= !(1 << i) }
+                        for &i in adds { val |= 1 << i }
+                        naive.insert(enumerate::to_best(&Graph::from_bits(size, val)).bits());
+                    }
+                }
+                assert_eq!(bump_depth(&gr, add, remove), naive, "{} {} {}", gr.bits(), add, remove);
+            }
+        }
+    }
+    #[test]
+    fn test_bump_vertex() {
+        let k2 = Graph::from_bits(2, 1);
+        assert_eq!(bump_vertex(&k2, true).len(), 3);
+        let c5 = Graph::from_fn(5, |a, b| b == a + 1 || (a == 0 && b == 4));
+        assert_eq!(bump_vertex(&c5, false).len(), 1);
+        // Isolated vertices given by a family are kept, and can be removed
+        let parsed = |s| crate::families::parse_bits(s).unwrap().graph();
+        let padded = bump_vertex(&parsed("union(cycle(5), complete(1))"), false);
+        assert_eq!(padded, BTreeSet::from([enumerate::to_best(&c5).bits(), 13]));
+        assert!(bump_vertex(&parsed("graph(6, 94)"), false).contains(&94));
+        let rng = &mut rand::thread_rng();
+        for _ in 0 .. 20 {
+            let size = rng.gen_range(2..=6);
+            let gr = enumerate::to_best(&random_graph(rng, size));
+            for bits in bump_vertex(&gr, true) {
+                let bigger = Graph::from_bits(size + 1, bits);
+                assert!(bump_vertex(&bigger, false).contains(&gr.bits()));
+            }
+        }
+    }
+    #[test]
     fn test_subgraph() {
         let rng = &mut rand::thread_rng();
         for _ in 0 .. 2000 {
@@ -243,5 +706,81 @@ mod tests {
             }
         }
     }
+    #[test]
+    fn test_pair() {
+        let rng = &mut rand::thread_rng();
+        let all = crate::enumerate::all_graphs(6);
+        for _ in 0 .. 20 {
+            let mut sub = random_graph(rng, 6);
+            let mut co = random_graph(rng, 6);
+            sub.edges.0.0 &= random_graph(rng, 6).edges.0.0;
+            co.edges.0.0 &= random_graph(rng, 6).edges.0.0;
+            let found: Vec<_> = noncovers_pair(all.iter().cloned(), &sub, &co).collect();
+            let naive: Vec<_> = all.iter().cloned().filter(|&bits| {
+                let sup = Graph::from_bits(6, bits);
+                !naive_is_subgraph_of(&sub, &sup) && !naive_is_subgraph_of(&co, &sup.complement())
+            }).collect();
+            assert_eq!(found, naive, "{} {}", sub.bits(), co.bits());
+            if let (Some(ce), _) = crate::seek::seek_pair(&sub, &co, usize::MAX) {
+                assert!(!is_subgraph_of(&sub, &ce) && !is_subgraph_of(&co, &ce.complement()));
+            }
+        }
+    }
+    #[test]
+    fn test_induced() {
+        let rng = &mut rand::thread_rng();
+        for _ in 0 .. 1000 {
+            let (sup_size, sub_size) = (rng.gen_range(1..=6), rng.gen_range(1..=4));
+            let sup = random_graph(rng, sup_size);
+            let sub = random_graph(rng, sub_size);
+            let found = find_induced(&sub, &build_sorted_row(&sub), &sup);
+            assert_eq!(found.is_some(), naive_is_induced_subgraph_of(&sub, &sup), "{} {}", sub, sup);
+            if let Some(map) = found {
+                for b in 1..sub.size { for a in 0..b {
+                    assert_eq!(sub.has_edge(a, b), sup.has_edge(map[a], map[b]));
+                }}
+            }
+        }
+        // Only complete and empty graphs lack an induced path of length 2 in
+        // themselves or their complements.
+        let all = crate::enumerate::all_graphs(5);
+        let target = Target::family(&[Graph::from_bits(5, 0b011)]).with_induced(Some(3));
+        assert_eq!(target.subs[0].0.size, 3);
+        let found: Vec<_> = noncovers_target(all.iter().cloned(), target.clone()).collect();
+        assert_eq!(found, vec![one_bits(10)]);
+        if let (Some(ce), _) = crate::seek::seek_target(&target, usize::MAX, None) {
+            assert!(!target.covers(&ce));
+        }
+        // An edge and an isolated vertex, however labelled, is missing only
+        // from complete and empty graphs; an edge alone is never missing.
+        let k2_k1 = |bits| Target::family(&[Graph::from_bits(5, bits)]).with_induced(Some(3));
+        for bits in [0b001, 0b010, 0b100] {
+            let found: Vec<_> = noncovers_target(all.iter().cloned(), k2_k1(bits)).collect();
+            assert_eq!(found, vec![one_bits(10)], "{}", bits);
+        }
+        let k2 = Target::family(&[Graph::from_bits(5, 1)]).with_induced(Some(2));
+        assert_eq!(noncovers_target(all.iter().cloned(), k2).next(), None);
+    }
+    #[test]
+    fn test_family() {
+        let rng = &mut rand::thread_rng();
+        let all = crate::enumerate::all_graphs(6);
+        let naive_covers = |grs: &[Graph], sup: &Graph| grs.iter()
+            .any(|gr| naive_is_subgraph_of(gr, sup) || naive_is_subgraph_of(gr, &sup.complement()));
+        for _ in 0 .. 20 {
+            let grs: Vec<_> = (0..rng.gen_range(1..=3)).map(|_| random_graph(rng, 6)).collect();
+            let target = Target::family(&grs);
+            assert!(target.is_symmetric());
+            let found: Vec<_> = noncovers_target(all.iter().cloned(), target.clone()).collect();
+            let naive: Vec<_> = all.iter().cloned().filter(|&bits| {
+                let sup = Graph::from_bits(6, bits);
+                2 * bits.count_ones() as usize >= Graph::triangle(6) && !naive_covers(&grs, &sup)
+            }).collect();
+            assert_eq!(found, naive);
+            if let (Some(ce), _) = crate::seek::seek_target(&target, usize::MAX, None) {
+                assert!(!naive_covers(&grs, &ce));
+            }
+        }
+    }
 }
 